@@ -0,0 +1,232 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use core::fmt;
+
+use crate::common::{days_from_civil, is_leap_year};
+use crate::DateComplete;
+
+/// A day of the week.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    /// Monday
+    Monday = 1,
+    /// Tuesday
+    Tuesday = 2,
+    /// Wednesday
+    Wednesday = 3,
+    /// Thursday
+    Thursday = 4,
+    /// Friday
+    Friday = 5,
+    /// Saturday
+    Saturday = 6,
+    /// Sunday
+    Sunday = 7,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// The ISO 8601 day number, where Monday is 1 and Sunday is 7.
+    pub fn number_from_monday(self) -> u32 {
+        self as u32
+    }
+
+    fn from_days_since_epoch(days: i64) -> Self {
+        // 1970-01-01 was a Thursday
+        Self::ALL[(days + 3).rem_euclid(7) as usize]
+    }
+}
+
+/// An ISO 8601 week: the year it belongs to, and its number in that year. Weeks begin on Monday,
+/// and week 1 is the week containing the year's first Thursday, so the first and last few days
+/// of a year can be in a week belonging to the year before or after.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoWeek {
+    year: i32,
+    week: u32,
+}
+
+impl IsoWeek {
+    /// The year this week belongs to. This can differ from the calendar year around new year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    /// The week number, from 1 to 52 or 53.
+    pub fn week(&self) -> u32 {
+        self.week
+    }
+}
+
+/// Formats as e.g. `2020-W53`.
+impl fmt::Display for IsoWeek {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = crate::helpers::sign_str_if_neg(self.year);
+        write!(f, "{}{:04}-W{:02}", sign, self.year.abs(), self.week)
+    }
+}
+
+/// 53 if the year starts on a Thursday, or is a leap year starting on a Wednesday.
+fn iso_weeks_in_year(year: i32) -> u32 {
+    let jan1 = Weekday::from_days_since_epoch(days_from_civil(year as i64, 1, 1));
+    match jan1 {
+        Weekday::Thursday => 53,
+        Weekday::Wednesday if is_leap_year(year) => 53,
+        _ => 52,
+    }
+}
+
+/// # Calendar arithmetic
+///
+/// These are computed natively, and work for any year, not only the ones chrono supports. The
+/// names differ from `chrono::Datelike`'s `weekday`, `ordinal` and `iso_week`, so those keep
+/// working when the trait is in scope.
+impl DateComplete {
+    /// Adds a (possibly negative) number of days. Returns None if the year would not fit in an
+    /// i32.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// let date = DateComplete::from_ymd(2020, 2, 28);
+    /// assert_eq!(date.add_days(2), Some(DateComplete::from_ymd(2020, 3, 1)));
+    /// assert_eq!(date.add_days(-366), Some(DateComplete::from_ymd(2019, 2, 27)));
+    /// ```
+    pub fn add_days(&self, days: i64) -> Option<Self> {
+        Self::from_days_since_epoch(self.days_since_epoch().checked_add(days)?)
+    }
+
+    /// The number of days from self to `other`, which is negative if `other` is earlier.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// let a = DateComplete::from_ymd(1999, 12, 31);
+    /// let b = DateComplete::from_ymd(2001, 1, 1);
+    /// assert_eq!(a.days_between(&b), 367);
+    /// assert_eq!(b.days_between(&a), -367);
+    /// ```
+    pub fn days_between(&self, other: &DateComplete) -> i64 {
+        other.days_since_epoch() - self.days_since_epoch()
+    }
+
+    /// The day of the week.
+    ///
+    /// ```
+    /// use edtf::{DateComplete, Weekday};
+    /// assert_eq!(DateComplete::from_ymd(2000, 1, 1).day_of_week(), Weekday::Saturday);
+    /// ```
+    pub fn day_of_week(&self) -> Weekday {
+        Weekday::from_days_since_epoch(self.days_since_epoch())
+    }
+
+    /// The day of the year, starting at 1 for January 1.
+    pub fn day_of_year(&self) -> u32 {
+        (self.days_since_epoch() - days_from_civil(self.year as i64, 1, 1)) as u32 + 1
+    }
+
+    /// The ISO 8601 week this day falls in. Returns None if the week belongs to a year that
+    /// does not fit in an i32, which can only happen in the first or last few days of
+    /// `i32::MIN` or `i32::MAX`.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// let week = DateComplete::from_ymd(2021, 1, 3).iso_week_opt().unwrap();
+    /// assert_eq!((week.year(), week.week()), (2020, 53));
+    /// assert_eq!(week.to_string(), "2020-W53");
+    /// ```
+    pub fn iso_week_opt(&self) -> Option<IsoWeek> {
+        let wd = self.day_of_week().number_from_monday() as i64;
+        let week = (self.day_of_year() as i64 - wd + 10) / 7;
+        let (year, week) = if week < 1 {
+            let year = self.year.checked_sub(1)?;
+            (year, iso_weeks_in_year(year))
+        } else if week as u32 > iso_weeks_in_year(self.year) {
+            (self.year.checked_add(1)?, 1)
+        } else {
+            (self.year, week as u32)
+        };
+        Some(IsoWeek { year, week })
+    }
+}
+
+#[test]
+fn weekdays_and_weeks() {
+    let ymd = DateComplete::from_ymd;
+    assert_eq!(ymd(1970, 1, 1).day_of_week(), Weekday::Thursday);
+    assert_eq!(ymd(1969, 12, 29).day_of_week(), Weekday::Monday);
+    assert_eq!(ymd(-1, 12, 31).day_of_week(), Weekday::Friday);
+    assert_eq!(ymd(2020, 12, 31).day_of_year(), 366);
+    assert_eq!(ymd(2021, 3, 1).day_of_year(), 60);
+    let week = |y, m, d| {
+        let w = ymd(y, m, d).iso_week_opt().unwrap();
+        (w.year(), w.week())
+    };
+    assert_eq!(week(2008, 12, 29), (2009, 1));
+    assert_eq!(week(2010, 1, 3), (2009, 53));
+    assert_eq!(week(2010, 1, 4), (2010, 1));
+    assert_eq!(week(2026, 10, 18), (2026, 42));
+    assert_eq!(
+        ymd(-5, 1, 1).iso_week_opt().unwrap().to_string(),
+        "-0006-W52"
+    );
+}
+
+#[test]
+fn arithmetic_extremes() {
+    let min = DateComplete::from_ymd(i32::MIN >> 4, 1, 1);
+    let max = DateComplete::from_ymd(i32::MAX >> 4, 12, 31);
+    let span = min.days_between(&max);
+    assert_eq!(min.add_days(span), Some(max));
+    assert_eq!(max.add_days(-span), Some(min));
+    assert_eq!(min.day_of_year(), 1);
+    assert_eq!(max.day_of_year(), 365 + is_leap_year(max.year()) as u32);
+    assert_eq!(
+        max.add_days(1).unwrap().day_of_week() as u32 % 7,
+        (max.day_of_week() as u32 + 1) % 7
+    );
+    assert!(max.iso_week_opt().unwrap().week() <= 53);
+    assert_eq!(DateComplete::from_ymd(2000, 1, 1).add_days(i64::MAX), None);
+}
+
+#[test]
+fn iso_week_i32_extremes() {
+    let ymd = DateComplete::from_ymd;
+    let week = |date: DateComplete| date.iso_week_opt().map(|w| (w.year(), w.week()));
+    // i32::MAX ends on a Tuesday, so its last two days are in week 1 of the year after
+    assert_eq!(ymd(i32::MAX, 12, 31).day_of_week(), Weekday::Tuesday);
+    assert_eq!(week(ymd(i32::MAX, 12, 31)), None);
+    assert_eq!(week(ymd(i32::MAX, 12, 30)), None);
+    assert_eq!(week(ymd(i32::MAX, 12, 29)), Some((i32::MAX, 52)));
+    // i32::MIN starts on a Tuesday, so its first week is its own
+    assert_eq!(ymd(i32::MIN, 1, 1).day_of_week(), Weekday::Tuesday);
+    assert_eq!(week(ymd(i32::MIN, 1, 1)), Some((i32::MIN, 1)));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn matches_chrono() {
+    use chrono::Datelike;
+    let start = DateComplete::from_ymd(1890, 1, 1);
+    for n in (0..100_000).step_by(13) {
+        let date = start.add_days(n).unwrap();
+        let naive = date.to_chrono();
+        assert_eq!(date.day_of_year(), naive.ordinal());
+        assert_eq!(
+            date.day_of_week().number_from_monday(),
+            naive.weekday().number_from_monday()
+        );
+        let (ours, theirs) = (date.iso_week_opt().unwrap(), naive.iso_week());
+        assert_eq!((ours.year(), ours.week()), (theirs.year(), theirs.week()));
+    }
+}
@@ -0,0 +1,291 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # BibTeX / biblatex date fields
+//!
+//! biblatex's `date` fields (`date`, `origdate`, `eventdate`, `urldate`) take the same ISO 8601-2
+//! syntax as level 1 EDTF, so a well-formed field parses as is. Real-world `.bib` files are not
+//! always well-formed, so [Edtf::from_biblatex] also accepts the usual workarounds:
+//!
+//! | Field                          | EDTF          |
+//! | -----                          | ----          |
+//! | `{1850}`                       | `1850`        |
+//! | `1850--1860`, `1850–1860`      | `1850/1860`   |
+//! | `circa 1850`, `ca. 1850`, `c.1850` | `1850~`   |
+//! | `[1850?]`                      | `1850?`       |
+//! | `ca. 1850?`                    | `1850%`       |
+//! | `1850/`, `1850/..`             | `1850/`, `1850/..` |
+//!
+//! The legacy `year` and `month` pair is read with [Edtf::from_biblatex_legacy], which accepts
+//! the same workarounds in `year` (`year = {1850?}`), and a number, a three-letter month macro
+//! or an English month name in `month`.
+//!
+//! ```
+//! use edtf::level_1::Edtf;
+//! let edtf = Edtf::from_biblatex("{ca. 1850--1860}").unwrap();
+//! assert_eq!(edtf.to_biblatex(), "1850~/1860");
+//!
+//! let edtf = Edtf::from_biblatex_legacy("1850?", Some("jun")).unwrap();
+//! assert_eq!(edtf.to_biblatex(), "1850-06?");
+//! ```
+
+use crate::level_1::{Certainty, Date, Edtf, Precision, Terminal};
+use crate::ParseError;
+
+/// Words and abbreviations meaning circa, lowercase, longest first so `ca.` wins over `c`.
+const CIRCA: [&str; 9] = [
+    "approximately",
+    "approx.",
+    "circa",
+    "about",
+    "ca.",
+    "ca",
+    "c.",
+    "c",
+    "~",
+];
+
+/// Strips whitespace and any number of enclosing braces.
+fn unwrap_braces(mut s: &str) -> &str {
+    loop {
+        s = s.trim();
+        match s.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+            Some(inner) => s = inner,
+            None => return s,
+        }
+    }
+}
+
+fn strip_prefix_no_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    match s.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&s[prefix.len()..]),
+        _ => None,
+    }
+}
+
+fn add_approximate(certainty: Certainty) -> Certainty {
+    match certainty {
+        Certainty::Certain | Certainty::Approximate => Certainty::Approximate,
+        Certainty::Uncertain | Certainty::ApproximateUncertain => Certainty::ApproximateUncertain,
+    }
+}
+
+/// One end of a range, e.g. `1850`, `[1850?]`, `ca. 1850-06`.
+fn point(s: &str) -> Result<Date, ParseError> {
+    let mut s = s.trim();
+    if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        s = inner.trim();
+    }
+    let mut approx = false;
+    for word in CIRCA.iter() {
+        if let Some(rest) = strip_prefix_no_case(s, word) {
+            // `c` and `ca` must not eat the start of something else
+            if rest.starts_with(|c: char| c.is_ascii_digit() || c == ' ' || c == '-') {
+                approx = true;
+                s = rest.trim_start();
+                break;
+            }
+        }
+    }
+    // `1850 ?`
+    let date = match s.rfind(|c: char| !"?~% ".contains(c)) {
+        Some(i) if s[i + 1..].contains(' ') => {
+            let quals: String = s[i + 1..].chars().filter(|&c| c != ' ').collect();
+            Date::parse(&format!("{}{}", &s[..=i], quals))?
+        }
+        _ => Date::parse(s)?,
+    };
+    Ok(if approx {
+        date.and_certainty(add_approximate(date.certainty()))
+    } else {
+        date
+    })
+}
+
+fn terminal_or_point(s: &str) -> Result<Result<Date, Terminal>, ParseError> {
+    match s.trim() {
+        "" => Ok(Err(Terminal::Unknown)),
+        ".." => Ok(Err(Terminal::Open)),
+        s => point(s).map(Ok),
+    }
+}
+
+/// Splits `a--b`, `a–b` or `a/b`.
+fn split_range(s: &str) -> Option<(&str, &str)> {
+    for sep in ["--", "\u{2013}", "/"].iter() {
+        if let Some(i) = s.find(sep) {
+            return Some((&s[..i], &s[i + sep.len()..]));
+        }
+    }
+    None
+}
+
+fn month_number(s: &str) -> Option<u32> {
+    let s = unwrap_braces(s);
+    if let Ok(n) = s.parse::<u32>() {
+        return Some(n).filter(|n| (1..=12).contains(n));
+    }
+    crate::common::english_month(s)
+}
+
+/// # biblatex
+impl Edtf {
+    /// Parses the contents of a biblatex date field, like `date = {1850/1860}`. See the
+    /// [crate::biblatex] module documentation for the workarounds this accepts on top of plain
+    /// EDTF.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// assert_eq!(Edtf::from_biblatex("c. 1850"), Edtf::parse("1850~"));
+    /// assert_eq!(Edtf::from_biblatex("1850--"), Edtf::parse("1850/"));
+    /// ```
+    pub fn from_biblatex(field: &str) -> Result<Self, ParseError> {
+        let s = unwrap_braces(field);
+        if let Ok(edtf) = Edtf::parse(s) {
+            return Ok(edtf);
+        }
+        let (a, b) = match split_range(s) {
+            Some(range) => range,
+            None => return point(s).map(Edtf::Date),
+        };
+        match (terminal_or_point(a)?, terminal_or_point(b)?) {
+            (Ok(a), Ok(b)) => Ok(Edtf::Interval(a, b)),
+            (Ok(a), Err(t)) => Ok(Edtf::IntervalFrom(a, t)),
+            (Err(t), Ok(b)) => Ok(Edtf::IntervalTo(t, b)),
+            (Err(_), Err(_)) => Err(ParseError::Invalid),
+        }
+    }
+
+    /// Parses the legacy `year` and `month` fields. `year` accepts the same workarounds as
+    /// [Edtf::from_biblatex], including ranges if there is no month. `month` may be a number, a
+    /// biblatex month macro like `jun`, or an English month name.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// assert_eq!(Edtf::from_biblatex_legacy("{1850}", Some("6")), Edtf::parse("1850-06"));
+    /// assert_eq!(Edtf::from_biblatex_legacy("circa 1850", None), Edtf::parse("1850~"));
+    /// ```
+    pub fn from_biblatex_legacy(year: &str, month: Option<&str>) -> Result<Self, ParseError> {
+        let edtf = Edtf::from_biblatex(year)?;
+        let month = match month.map(unwrap_braces) {
+            None | Some("") => return Ok(edtf),
+            Some(m) => month_number(m).ok_or(ParseError::Invalid)?,
+        };
+        match edtf {
+            Edtf::Date(d) => match d.precision() {
+                Precision::Year(y) => Date::from_ymd_opt(y, month, 0)
+                    .map(|m| Edtf::Date(m.and_certainty(d.certainty())))
+                    .ok_or(ParseError::OutOfRange),
+                _ => Err(ParseError::Invalid),
+            },
+            _ => Err(ParseError::Invalid),
+        }
+    }
+
+    /// Formats as a biblatex date field. biblatex reads level 1 EDTF, so this is the same as the
+    /// [Display](core::fmt::Display) output, without any of the workarounds
+    /// [Edtf::from_biblatex] accepts.
+    pub fn to_biblatex(&self) -> String {
+        self.to_string()
+    }
+
+    /// Formats as the legacy `year` and `month` fields, where the month is a number. Returns None
+    /// if the date needs the `date` field, i.e. it is an interval, has a day, is not certain, or
+    /// has unspecified digits.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let edtf = Edtf::parse("1850-06").unwrap();
+    /// assert_eq!(edtf.to_biblatex_legacy(), Some(("1850".to_string(), Some(6))));
+    /// assert_eq!(Edtf::parse("1850?").unwrap().to_biblatex_legacy(), None);
+    /// ```
+    pub fn to_biblatex_legacy(&self) -> Option<(String, Option<u32>)> {
+        let d = self.as_date()?;
+        if d.certainty() != Certainty::Certain {
+            return None;
+        }
+        match d.precision() {
+            Precision::Year(y) => Some((Date::from_year(y).to_string(), None)),
+            Precision::Month(y, m) => Some((Date::from_year(y).to_string(), Some(m))),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn biblatex_parse() {
+    let bib = |s: &str| Edtf::from_biblatex(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(bib("1850"), ok("1850"));
+    assert_eq!(bib("{{1850-06-15}}"), ok("1850-06-15"));
+    assert_eq!(bib("1850/1860"), ok("1850/1860"));
+    assert_eq!(bib("1850/.."), ok("1850/.."));
+    assert_eq!(bib("../1850"), ok("../1850"));
+    assert_eq!(bib("1850?/1860~"), ok("1850?/1860~"));
+    assert_eq!(bib("Y-170000002"), ok("Y-170000002"));
+    assert_eq!(bib("1850-22"), ok("1850-22"));
+    assert_eq!(bib("185X"), ok("185X"));
+    assert_eq!(bib("1850--1860"), ok("1850/1860"));
+    assert_eq!(bib("1850 -- 1860"), ok("1850/1860"));
+    assert_eq!(bib("1850\u{2013}1860"), ok("1850/1860"));
+    assert_eq!(bib("1850--"), ok("1850/"));
+    assert_eq!(bib("1850--.."), ok("1850/.."));
+    assert_eq!(bib("circa 1850"), ok("1850~"));
+    assert_eq!(bib("Circa 1850"), ok("1850~"));
+    assert_eq!(bib("ca. 1850"), ok("1850~"));
+    assert_eq!(bib("ca 1850"), ok("1850~"));
+    assert_eq!(bib("c.1850"), ok("1850~"));
+    assert_eq!(bib("c1850"), ok("1850~"));
+    assert_eq!(bib("~1850"), ok("1850~"));
+    assert_eq!(bib("about 1850-06"), ok("1850-06~"));
+    assert_eq!(bib("ca. 1850?"), ok("1850%"));
+    assert_eq!(bib("[1850]"), ok("1850"));
+    assert_eq!(bib("[1850?]"), ok("1850?"));
+    assert_eq!(bib("1850 ?"), ok("1850?"));
+    assert_eq!(bib("ca. 1850--ca. 1860"), ok("1850~/1860~"));
+    assert_eq!(bib("--"), Err(ParseError::Invalid));
+    assert_eq!(bib("cat 1850"), Err(ParseError::Invalid));
+    assert_eq!(bib("June 1850"), Err(ParseError::Invalid));
+}
+
+#[test]
+fn biblatex_legacy() {
+    let bib = |y: &str, m: Option<&str>| Edtf::from_biblatex_legacy(y, m).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(bib("1850", None), ok("1850"));
+    assert_eq!(bib("1850", Some("")), ok("1850"));
+    assert_eq!(bib("1850?", None), ok("1850?"));
+    assert_eq!(bib("1850--1860", None), ok("1850/1860"));
+    assert_eq!(bib("1850", Some("6")), ok("1850-06"));
+    assert_eq!(bib("1850", Some("{06}")), ok("1850-06"));
+    assert_eq!(bib("1850", Some("jun")), ok("1850-06"));
+    assert_eq!(bib("1850", Some("June")), ok("1850-06"));
+    assert_eq!(bib("1850", Some("Sept.")), ok("1850-09"));
+    assert_eq!(bib("ca. 1850", Some("dec")), ok("1850-12~"));
+    assert_eq!(bib("1850", Some("13")), Err(ParseError::Invalid));
+    assert_eq!(bib("1850", Some("ju")), Err(ParseError::Invalid));
+    assert_eq!(bib("1850--1860", Some("jun")), Err(ParseError::Invalid));
+    assert_eq!(bib("185X", Some("jun")), Err(ParseError::Invalid));
+}
+
+#[test]
+fn biblatex_format() {
+    let edtf = |s: &str| Edtf::parse(s).unwrap();
+    assert_eq!(edtf("1850~/1860").to_biblatex(), "1850~/1860");
+    assert_eq!(edtf("1850/..").to_biblatex(), "1850/..");
+    assert_eq!(
+        edtf("1850").to_biblatex_legacy(),
+        Some(("1850".into(), None))
+    );
+    assert_eq!(
+        edtf("0850-01").to_biblatex_legacy(),
+        Some(("0850".into(), Some(1)))
+    );
+    assert_eq!(edtf("185X").to_biblatex_legacy(), None);
+    assert_eq!(edtf("1850-XX").to_biblatex_legacy(), None);
+    assert_eq!(edtf("1850-06-15").to_biblatex_legacy(), None);
+    assert_eq!(edtf("1850/1860").to_biblatex_legacy(), None);
+}
@@ -0,0 +1,120 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use core::convert::TryInto;
+
+use super::Calendar;
+
+/// 1 Vendémiaire an I, 1792-09-22, in days since 1970-01-01.
+const EPOCH: i64 = -64_748;
+
+/// The French Republican calendar.
+///
+/// Months 1 to 12 (Vendémiaire to Fructidor) have 30 days each. Month 13 holds the five or six
+/// complementary days (*jours complémentaires*, or *sansculottides*) at the end of the year.
+///
+/// In use, each year began on the day of the autumn equinox in Paris, which made years 3, 7,
+/// 11 and 15 sextile (leap) years. Those are used for years up to 19, which covers the
+/// calendar's official use. From year 20 this uses the arithmetic rule proposed by Romme:
+/// every fourth year, except centuries not divisible by 400, and multiples of 4000. So dates
+/// from the Paris Commune, in year 79, follow the arithmetic rule. Years before 1 are not
+/// valid.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FrenchRepublican;
+
+fn is_leap_year(year: i64) -> bool {
+    if year < 20 {
+        year % 4 == 3 && year < 19
+    } else {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) && year % 4000 != 0
+    }
+}
+
+/// Number of leap years from year 1 up to and including `year`.
+fn leap_years_until(year: i64) -> i64 {
+    if year < 20 {
+        ((year + 1) / 4).min(4)
+    } else {
+        // the four sextile years up to 19 happen to equal the arithmetic count
+        year / 4 - year / 100 + year / 400 - year / 4000
+    }
+}
+
+fn days_before_year(year: i64) -> i64 {
+    365 * (year - 1) + leap_years_until(year - 1)
+}
+
+impl Calendar for FrenchRepublican {
+    fn months_in_year(&self, _year: i32) -> u32 {
+        13
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> u32 {
+        match month {
+            13 if is_leap_year(year as i64) => 6,
+            13 => 5,
+            _ => 30,
+        }
+    }
+
+    fn is_valid_year(&self, year: i32) -> bool {
+        year >= 1
+    }
+
+    fn days_since_epoch(&self, year: i32, month: u32, day: u32) -> Option<i64> {
+        let day_of_year = 30 * (month as i64 - 1) + day as i64 - 1;
+        Some(EPOCH + days_before_year(year as i64) + day_of_year)
+    }
+
+    fn date_from_days_since_epoch(&self, days: i64) -> Option<(i32, u32, u32)> {
+        let since = days.checked_sub(EPOCH)?;
+        if since < 0 {
+            return None;
+        }
+        // may be one too many
+        let mut year = since * 400 / 146_097 + 1;
+        while days_before_year(year) > since {
+            year -= 1;
+        }
+        while days_before_year(year + 1) <= since {
+            year += 1;
+        }
+        let day_of_year = since - days_before_year(year);
+        let year = year.try_into().ok()?;
+        Some((
+            year,
+            (day_of_year / 30) as u32 + 1,
+            (day_of_year % 30) as u32 + 1,
+        ))
+    }
+}
+
+#[test]
+fn french_republican() {
+    use crate::DateComplete;
+    let ymd = DateComplete::from_ymd;
+    let pairs = [
+        ((1, 1, 1), ymd(1792, 9, 22)),
+        // 9 Thermidor an II
+        ((2, 11, 9), ymd(1794, 7, 27)),
+        // 18 Brumaire an VIII
+        ((8, 2, 18), ymd(1799, 11, 9)),
+        // the sixth complementary day of the sextile year III
+        ((3, 13, 6), ymd(1795, 9, 22)),
+        ((4, 1, 1), ymd(1795, 9, 23)),
+        ((14, 1, 1), ymd(1805, 9, 23)),
+        // 16 Floréal an LXXIX, during the Paris Commune
+        ((79, 8, 16), ymd(1871, 5, 6)),
+    ];
+    for &((y, m, d), greg) in pairs.iter() {
+        assert_eq!(FrenchRepublican.to_gregorian(y, m, d), Some(greg));
+        assert_eq!(FrenchRepublican.date_from_gregorian(greg), Some((y, m, d)));
+    }
+    assert_eq!(FrenchRepublican.to_gregorian(2, 13, 6), None);
+    assert_eq!(FrenchRepublican.to_gregorian(0, 1, 1), None);
+    assert_eq!(FrenchRepublican.date_from_gregorian(ymd(1792, 9, 21)), None);
+    super::roundtrip_all(&FrenchRepublican, 1..=500);
+}
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use core::convert::TryInto;
+
+use super::Calendar;
+
+/// 1 Tishri AM 1, Julian -3760-10-07, in days since 1970-01-01.
+const EPOCH: i64 = -2_092_590;
+
+/// The Hebrew calendar.
+///
+/// Years are Anno Mundi, and begin with Tishri. Months are numbered in the order they occur, so
+/// Tishri is 1 and Elul is the last month. In leap years, Adar I is month 6 and Adar II is month
+/// 7; in common years Adar is month 6.
+///
+/// | Month | Common year | Leap year |
+/// | ----- | ----------- | --------- |
+/// | 1     | Tishri      | Tishri    |
+/// | 2     | Heshvan     | Heshvan   |
+/// | 3     | Kislev      | Kislev    |
+/// | 4     | Tevet       | Tevet     |
+/// | 5     | Shevat      | Shevat    |
+/// | 6     | Adar        | Adar I    |
+/// | 7     | Nisan       | Adar II   |
+/// | 8     | Iyar        | Nisan     |
+/// | 9     | Sivan       | Iyar      |
+/// | 10    | Tammuz      | Sivan     |
+/// | 11    | Av          | Tammuz    |
+/// | 12    | Elul        | Av        |
+/// | 13    |             | Elul      |
+///
+/// The arithmetic follows Reingold and Dershowitz, *Calendrical Calculations*.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Hebrew;
+
+fn is_leap_year(year: i64) -> bool {
+    (7 * year + 1).rem_euclid(19) < 7
+}
+
+/// Days from the epoch to the molad of Tishri, postponed if it falls on a Sunday, Wednesday or
+/// Friday.
+fn elapsed_days(year: i64) -> i64 {
+    let months = (235 * year - 234).div_euclid(19);
+    let parts = 12_084 + 13_753 * months;
+    let days = 29 * months + parts.div_euclid(25_920);
+    if (3 * (days + 1)).rem_euclid(7) < 3 {
+        days + 1
+    } else {
+        days
+    }
+}
+
+/// Further postponements, to keep years within their allowed lengths.
+fn year_length_correction(year: i64) -> i64 {
+    let ny0 = elapsed_days(year - 1);
+    let ny1 = elapsed_days(year);
+    let ny2 = elapsed_days(year + 1);
+    if ny2 - ny1 == 356 {
+        2
+    } else if ny1 - ny0 == 382 {
+        1
+    } else {
+        0
+    }
+}
+
+/// 1 Tishri, in days since 1970-01-01.
+fn new_year(year: i64) -> i64 {
+    EPOCH + elapsed_days(year) + year_length_correction(year)
+}
+
+fn days_in_year(year: i64) -> i64 {
+    new_year(year + 1) - new_year(year)
+}
+
+impl Calendar for Hebrew {
+    fn months_in_year(&self, year: i32) -> u32 {
+        if is_leap_year(year as i64) {
+            13
+        } else {
+            12
+        }
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> u32 {
+        let leap = is_leap_year(year as i64);
+        let year_length = days_in_year(year as i64);
+        // skip Adar I in common years, so months line up with leap years
+        let month = if !leap && month >= 6 {
+            month + 1
+        } else {
+            month
+        };
+        match month {
+            // Heshvan is long in complete years
+            2 if year_length % 10 == 5 => 30,
+            2 => 29,
+            // Kislev is short in deficient years
+            3 if year_length % 10 == 3 => 29,
+            3 => 30,
+            // Adar I is 30 days; Adar in a common year is 29
+            6 => 30,
+            4 | 7 | 9 | 11 | 13 => 29,
+            _ => 30,
+        }
+    }
+
+    fn days_since_epoch(&self, year: i32, month: u32, day: u32) -> Option<i64> {
+        let before: i64 = (1..month).map(|m| self.days_in_month(year, m) as i64).sum();
+        Some(new_year(year as i64) + before + day as i64 - 1)
+    }
+
+    fn date_from_days_since_epoch(&self, days: i64) -> Option<(i32, u32, u32)> {
+        // average year length is 35975351/98496 days
+        let approx = (98_496i64.checked_mul(days.checked_sub(EPOCH)?)?).div_euclid(35_975_351) + 1;
+        let mut year = approx - 1;
+        while new_year(year + 1) <= days {
+            year += 1;
+        }
+        let year: i32 = year.try_into().ok()?;
+        let mut remaining = days - new_year(year as i64);
+        for month in 1..=self.months_in_year(year) {
+            let len = self.days_in_month(year, month) as i64;
+            if remaining < len {
+                return Some((year, month, remaining as u32 + 1));
+            }
+            remaining -= len;
+        }
+        None
+    }
+}
+
+#[test]
+fn hebrew() {
+    use crate::DateComplete;
+    let ymd = DateComplete::from_ymd;
+    let pairs = [
+        ((5784, 1, 1), ymd(2023, 9, 16)),
+        // 15 Nisan 5784, a leap year
+        ((5784, 8, 15), ymd(2024, 4, 23)),
+        // 15 Nisan 5783, a common year
+        ((5783, 7, 15), ymd(2023, 4, 6)),
+        // 14 Adar II 5784 (Purim)
+        ((5784, 7, 14), ymd(2024, 3, 24)),
+        ((5785, 1, 1), ymd(2024, 10, 3)),
+        ((5760, 4, 23), ymd(2000, 1, 1)),
+        ((1, 1, 1), ymd(-3760, 9, 7)),
+    ];
+    for &((y, m, d), greg) in pairs.iter() {
+        assert_eq!(Hebrew.to_gregorian(y, m, d), Some(greg));
+        assert_eq!(Hebrew.date_from_gregorian(greg), Some((y, m, d)));
+    }
+    // 5783 is complete (355 days), 5784 is deficient (383 days)
+    assert_eq!(Hebrew.days_in_month(5783, 2), 30);
+    assert_eq!(Hebrew.days_in_month(5783, 3), 30);
+    assert_eq!(Hebrew.days_in_month(5784, 2), 29);
+    assert_eq!(Hebrew.days_in_month(5784, 3), 29);
+    assert_eq!(Hebrew.to_gregorian(5783, 13, 1), None);
+    assert_eq!(Hebrew.to_gregorian(5784, 4, 30), None);
+    super::roundtrip_all(&Hebrew, 5700..=5800);
+    super::roundtrip_all(&Hebrew, 1..=40);
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::Calendar;
+
+/// 1 Muharram 1 AH, Julian 622-07-16, in days since 1970-01-01.
+const EPOCH: i64 = -492_148;
+
+/// The tabular Islamic (Hijri) calendar.
+///
+/// Months alternate between 30 and 29 days, and 11 years in each 30 year cycle have a 30 day
+/// Dhu al-Hijja (month 12). Calendars based on sighting the new moon, including the one used
+/// in the Ottoman Empire, can differ from this by a day or two.
+///
+/// This uses the civil epoch, 1 Muharram 1 AH = Julian 622-07-16, and the most common leap
+/// years, 2, 5, 7, 10, 13, 16, 18, 21, 24, 26 and 29.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Hijri;
+
+fn is_leap_year(year: i64) -> bool {
+    (14 + 11 * year).rem_euclid(30) < 11
+}
+
+fn days_before_year(year: i64) -> i64 {
+    (year - 1) * 354 + (3 + 11 * year).div_euclid(30)
+}
+
+fn days_before_month(month: i64) -> i64 {
+    29 * (month - 1) + month / 2
+}
+
+impl Calendar for Hijri {
+    fn months_in_year(&self, _year: i32) -> u32 {
+        12
+    }
+
+    fn days_in_month(&self, year: i32, month: u32) -> u32 {
+        match month {
+            12 if is_leap_year(year as i64) => 30,
+            m if m % 2 == 1 => 30,
+            _ => 29,
+        }
+    }
+
+    fn days_since_epoch(&self, year: i32, month: u32, day: u32) -> Option<i64> {
+        Some(
+            EPOCH - 1
+                + days_before_year(year as i64)
+                + days_before_month(month as i64)
+                + day as i64,
+        )
+    }
+
+    fn date_from_days_since_epoch(&self, days: i64) -> Option<(i32, u32, u32)> {
+        let since = days.checked_sub(EPOCH)?;
+        let year = (30i64.checked_mul(since)? + 10_646).div_euclid(10_631);
+        let start_of_year = EPOCH + days_before_year(year);
+        let month = ((11 * (days - start_of_year) + 330) / 325).clamp(1, 12);
+        let day = days - (start_of_year + days_before_month(month)) + 1;
+        let year = core::convert::TryInto::try_into(year).ok()?;
+        Some((year, month as u32, day as u32))
+    }
+}
+
+#[test]
+fn hijri() {
+    use crate::DateComplete;
+    let ymd = DateComplete::from_ymd;
+    let pairs = [
+        ((1, 1, 1), ymd(622, 7, 19)),
+        ((1445, 1, 1), ymd(2023, 7, 19)),
+        ((1445, 9, 1), ymd(2024, 3, 11)),
+        ((1445, 12, 30), ymd(2024, 7, 7)),
+        ((1446, 1, 1), ymd(2024, 7, 8)),
+        ((1000, 1, 1), ymd(1591, 10, 19)),
+    ];
+    for &((y, m, d), greg) in pairs.iter() {
+        assert_eq!(Hijri.to_gregorian(y, m, d), Some(greg));
+        assert_eq!(Hijri.date_from_gregorian(greg), Some((y, m, d)));
+    }
+    assert_eq!(Hijri.to_gregorian(1444, 12, 30), None);
+    assert_eq!(Hijri.to_gregorian(1445, 2, 30), None);
+    assert_eq!(Hijri.to_gregorian(1445, 13, 1), None);
+    super::roundtrip_all(&Hijri, -60..=60);
+    super::roundtrip_all(&Hijri, 1400..=1460);
+}
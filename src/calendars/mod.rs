@@ -0,0 +1,146 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Other calendars
+//!
+//! Arithmetic conversions from other calendars into the proleptic Gregorian calendar EDTF uses.
+//! Every calendar implements [Calendar], so code can be written once for all of them, and new
+//! calendars can be added outside this crate.
+//!
+//! - [Hijri], the tabular Islamic calendar
+//! - [Hebrew]
+//! - [FrenchRepublican]
+//!
+//! Dates are given as `(year, month, day)`, with months numbered from 1 in the order they occur
+//! in the year. As elsewhere in this crate, a month or day of `0` means that component is absent,
+//! and a whole month or year converts to an interval of Gregorian days.
+//!
+//! ```
+//! use edtf::calendars::{Calendar, FrenchRepublican, Hebrew, Hijri};
+//! use edtf::level_1::Edtf;
+//! use edtf::DateComplete;
+//!
+//! // 18 Brumaire an VIII
+//! let date = FrenchRepublican.to_gregorian(8, 2, 18);
+//! assert_eq!(date, Some(DateComplete::from_ymd(1799, 11, 9)));
+//!
+//! // Tishri 5784
+//! let month = Hebrew.to_edtf(5784, 1, 0);
+//! assert_eq!(month, Some(Edtf::parse("2023-09-16/2023-10-15").unwrap()));
+//!
+//! let year = Hijri.to_edtf(1445, 0, 0);
+//! assert_eq!(year, Some(Edtf::parse("2023-07-19/2024-07-07").unwrap()));
+//! ```
+
+mod french;
+mod hebrew;
+mod hijri;
+
+pub use french::FrenchRepublican;
+pub use hebrew::Hebrew;
+pub use hijri::Hijri;
+
+use crate::level_1::Edtf;
+use crate::DateComplete;
+
+/// A calendar that can be converted to and from the proleptic Gregorian calendar by counting
+/// days.
+///
+/// Implementors only need to describe the shape of their years and months, and how to count
+/// days; the provided methods do validation and conversion. Days are counted from 1970-01-01
+/// (Gregorian), which is day 0.
+pub trait Calendar {
+    /// The number of months in `year`.
+    fn months_in_year(&self, year: i32) -> u32;
+
+    /// The number of days in a month. Only called with a valid year and month.
+    fn days_in_month(&self, year: i32, month: u32) -> u32;
+
+    /// Days since 1970-01-01 of a date. Only called with a valid date. Returns None on
+    /// overflow.
+    fn days_since_epoch(&self, year: i32, month: u32, day: u32) -> Option<i64>;
+
+    /// The date that falls on a number of days since 1970-01-01. Returns None if it is outside
+    /// the calendar, or on overflow.
+    fn date_from_days_since_epoch(&self, days: i64) -> Option<(i32, u32, u32)>;
+
+    /// Whether the calendar has a year. All years are valid by default.
+    fn is_valid_year(&self, _year: i32) -> bool {
+        true
+    }
+
+    /// Whether a date exists in this calendar.
+    fn is_valid(&self, year: i32, month: u32, day: u32) -> bool {
+        self.is_valid_year(year)
+            && (1..=self.months_in_year(year)).contains(&month)
+            && (1..=self.days_in_month(year, month)).contains(&day)
+    }
+
+    /// Converts a date to the proleptic Gregorian calendar. Returns None if the date is invalid
+    /// or out of range.
+    fn to_gregorian(&self, year: i32, month: u32, day: u32) -> Option<DateComplete> {
+        if !self.is_valid(year, month, day) {
+            return None;
+        }
+        DateComplete::from_days_since_epoch(self.days_since_epoch(year, month, day)?)
+    }
+
+    /// Converts a proleptic Gregorian date to `(year, month, day)` in this calendar.
+    fn date_from_gregorian(&self, date: DateComplete) -> Option<(i32, u32, u32)> {
+        self.date_from_days_since_epoch(date.days_since_epoch())
+    }
+
+    /// Converts a day, month or year to EDTF. Pass `0` for an absent day, or an absent month and
+    /// day. A month or year becomes the interval of days it covers, in its most compact form.
+    ///
+    /// Returns None if the date is invalid or out of range.
+    fn to_edtf(&self, year: i32, month: u32, day: u32) -> Option<Edtf> {
+        let (first, last) = match (month, day) {
+            (0, 0) => {
+                let months = self.months_in_year(year);
+                (
+                    self.to_gregorian(year, 1, 1)?,
+                    self.to_gregorian(year, months, self.days_in_month(year, months))?,
+                )
+            }
+            (0, _) => return None,
+            (m, 0) => {
+                if !self.is_valid(year, m, 1) {
+                    return None;
+                }
+                (
+                    self.to_gregorian(year, m, 1)?,
+                    self.to_gregorian(year, m, self.days_in_month(year, m))?,
+                )
+            }
+            (m, d) => {
+                let date = self.to_gregorian(year, m, d)?;
+                (date, date)
+            }
+        };
+        Edtf::from_day_range(first, last)
+    }
+}
+
+#[cfg(test)]
+fn roundtrip_all<C: Calendar>(cal: &C, years: core::ops::RangeInclusive<i32>) {
+    let mut expected = None;
+    for year in years {
+        for month in 1..=cal.months_in_year(year) {
+            for day in 1..=cal.days_in_month(year, month) {
+                let days = cal.days_since_epoch(year, month, day).unwrap();
+                if let Some(prev) = expected {
+                    assert_eq!(days, prev, "{}-{}-{} not contiguous", year, month, day);
+                }
+                assert_eq!(
+                    cal.date_from_days_since_epoch(days),
+                    Some((year, month, day))
+                );
+                expected = Some(days + 1);
+            }
+        }
+    }
+}
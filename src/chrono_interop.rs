@@ -174,8 +174,47 @@ impl From<NaiveDate> for crate::level_1::Date {
     }
 }
 
+impl From<crate::Weekday> for chrono::Weekday {
+    fn from(weekday: crate::Weekday) -> Self {
+        use crate::Weekday as W;
+        match weekday {
+            W::Monday => chrono::Weekday::Mon,
+            W::Tuesday => chrono::Weekday::Tue,
+            W::Wednesday => chrono::Weekday::Wed,
+            W::Thursday => chrono::Weekday::Thu,
+            W::Friday => chrono::Weekday::Fri,
+            W::Saturday => chrono::Weekday::Sat,
+            W::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
+/// Fails if the week's year is outside the range chrono supports.
+impl TryFrom<crate::IsoWeek> for chrono::IsoWeek {
+    type Error = ();
+    fn try_from(week: crate::IsoWeek) -> Result<Self, Self::Error> {
+        NaiveDate::from_isoywd_opt(week.year(), week.week(), chrono::Weekday::Mon)
+            .map(|monday| monday.iso_week())
+            .ok_or(())
+    }
+}
+
 /// Convenience [chrono::Datelike] implementation, which mostly relies on internal conversion to
 /// [chrono::NaiveDate].
+///
+/// The native [DateComplete::day_of_week] and [DateComplete::iso_week_opt] work without chrono,
+/// and their results convert to chrono's types with `into()` and `try_into()`.
+///
+/// ```
+/// use chrono::Datelike;
+/// use core::convert::TryInto;
+/// use edtf::DateComplete;
+/// let date = DateComplete::from_ymd(2021, 1, 3);
+/// let weekday: chrono::Weekday = date.day_of_week().into();
+/// assert_eq!(weekday, date.weekday());
+/// let week: chrono::IsoWeek = date.iso_week_opt().unwrap().try_into().unwrap();
+/// assert_eq!(week, date.iso_week());
+/// ```
 impl Datelike for DateComplete {
     fn year(&self) -> i32 {
         self.year
@@ -198,10 +237,10 @@ impl Datelike for DateComplete {
     }
 
     fn ordinal(&self) -> u32 {
-        self.to_chrono().ordinal()
+        self.day_of_year()
     }
     fn ordinal0(&self) -> u32 {
-        self.to_chrono().ordinal0()
+        self.day_of_year() - 1
     }
 
     fn weekday(&self) -> chrono::Weekday {
@@ -480,6 +519,22 @@ fn timezone_impl_unspec() {
     );
 }
 
+#[test]
+fn weekday_and_iso_week_into_chrono() {
+    let start = DateComplete::from_ymd(2019, 12, 20);
+    for n in 0..30 {
+        let date = start.add_days(n).unwrap();
+        let weekday: chrono::Weekday = date.day_of_week().into();
+        assert_eq!(weekday, date.weekday());
+        let week = chrono::IsoWeek::try_from(date.iso_week_opt().unwrap());
+        assert_eq!(week, Ok(date.iso_week()));
+    }
+    let far = DateComplete::from_ymd(1_000_000, 6, 1)
+        .iso_week_opt()
+        .unwrap();
+    assert_eq!(chrono::IsoWeek::try_from(far), Err(()));
+}
+
 #[cfg(test)]
 mod test {
     #[test]
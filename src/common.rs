@@ -45,6 +45,78 @@ fn leap_year() {
 pub(crate) const MONTH_DAYCOUNT: [u8; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 pub(crate) const MONTH_DAYCOUNT_LEAP: [u8; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
 
+pub(crate) fn days_in_month(year: i32, month: u8) -> u8 {
+    let lut = if is_leap_year(year) {
+        MONTH_DAYCOUNT_LEAP
+    } else {
+        MONTH_DAYCOUNT
+    };
+    lut[month as usize - 1]
+}
+
+/// Number of days since 1970-01-01 in the proleptic Gregorian calendar.
+/// From Howard Hinnant's `days_from_civil`.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let (m, d) = (month as i64, day as i64);
+    let y = if m <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// The inverse of [days_from_civil].
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m as u32, d as u32)
+}
+
+#[test]
+fn civil_days() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 3, 1), 11_017);
+    assert_eq!(days_from_civil(1969, 12, 31), -1);
+    assert_eq!(days_from_civil(0, 1, 1), -719_528);
+    for &days in &[-1_000_000_000, -719_528, -1, 0, 1, 11_017, 1_000_000_000] {
+        let (y, m, d) = civil_from_days(days);
+        assert_eq!(days_from_civil(y, m, d), days);
+    }
+    assert_eq!(civil_from_days(-719_529), (-1, 12, 31));
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// An English month name or an abbreviation of at least three letters, like `Sept.`.
+pub(crate) fn english_month(s: &str) -> Option<u32> {
+    let lower = s.trim_end_matches('.').to_ascii_lowercase();
+    ENGLISH_MONTHS
+        .iter()
+        .position(|&name| name == lower || (lower.len() >= 3 && name.starts_with(lower.as_str())))
+        .map(|i| i as u32 + 1)
+}
+
 pub(crate) fn is_valid_complete_date(
     year: i32,
     month: u8,
@@ -0,0 +1,306 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Deep time
+//!
+//! Geology and cosmology count years back from the present in thousands (`ka`), millions (`Ma`)
+//! or billions (`Ga`) of years. [DeepTime] converts between those quantities and the very large
+//! years of a level 2 [ScientificYear] or a level 1 [YYear](crate::level_1::YYear), relative to
+//! a configurable present.
+//!
+//! The significant digits of the written quantity become the significant digits of the year:
+//! the year is rounded to as many digits as were written. Counting from 1950, `66 Ma` is the
+//! year -65,998,050, which to two significant digits is `Y-66E6S2`, and `1.2 ka` is the year
+//! 750, or `Y75E1S2`.
+//!
+//! ```
+//! use edtf::deep_time::{DeepTime, DeepTimeUnit};
+//! use edtf::level_2::Edtf;
+//!
+//! let deep = DeepTime::default();
+//! assert_eq!(deep.parse("66 Ma"), Edtf::parse("Y-66E6S2"));
+//! assert_eq!(deep.parse("2.5 Ga"), Edtf::parse("Y-25E8S2"));
+//! assert_eq!(deep.parse("12 ka BP"), Edtf::parse("Y-10E3S2"));
+//!
+//! let year = match deep.parse("66 Ma").unwrap() {
+//!     Edtf::Scientific(year) => year,
+//!     _ => unreachable!(),
+//! };
+//! let ma = deep.in_units(year.range(), DeepTimeUnit::Mega).unwrap();
+//! assert_eq!(ma, 65.001_951..=66.001_95);
+//! ```
+
+use core::convert::TryInto;
+use core::ops::RangeInclusive;
+
+use crate::era::{n_digits, parse_number, PRESENT};
+use crate::level_2::{Edtf, ScientificYear};
+use crate::ParseError;
+
+/// A unit of years for deep time.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeepTimeUnit {
+    /// Years, written `a`, `yr` or `years`
+    Year,
+    /// Thousands of years, written `ka` or `kyr`
+    Kilo,
+    /// Millions of years, written `Ma` or `Myr`
+    Mega,
+    /// Billions of years, written `Ga` or `Gyr`
+    Giga,
+}
+
+impl DeepTimeUnit {
+    /// The power of ten years this unit counts in.
+    pub fn exponent(self) -> u32 {
+        match self {
+            DeepTimeUnit::Year => 0,
+            DeepTimeUnit::Kilo => 3,
+            DeepTimeUnit::Mega => 6,
+            DeepTimeUnit::Giga => 9,
+        }
+    }
+
+    /// The usual symbol, e.g. `Ma`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            DeepTimeUnit::Year => "a",
+            DeepTimeUnit::Kilo => "ka",
+            DeepTimeUnit::Mega => "Ma",
+            DeepTimeUnit::Giga => "Ga",
+        }
+    }
+
+    fn from_symbol(s: &str) -> Option<Self> {
+        Some(match s.to_ascii_lowercase().as_str() {
+            "a" | "yr" | "yrs" | "year" | "years" => DeepTimeUnit::Year,
+            "ka" | "kyr" | "kya" => DeepTimeUnit::Kilo,
+            "ma" | "myr" | "mya" => DeepTimeUnit::Mega,
+            "ga" | "gyr" | "bya" => DeepTimeUnit::Giga,
+            _ => return None,
+        })
+    }
+}
+
+/// Converts between deep time quantities and EDTF years. See the [module level
+/// documentation](crate::deep_time).
+///
+/// Quantities are counted back from a present year, which is 1950 by default, as in radiocarbon
+/// dating's Before Present. Use [DeepTime::with_present] for another convention, like 2000 for
+/// `b2k`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DeepTime {
+    present: i64,
+}
+
+impl Default for DeepTime {
+    fn default() -> Self {
+        DeepTime { present: PRESENT }
+    }
+}
+
+impl DeepTime {
+    /// Counts years back from `present`, an astronomical year.
+    pub fn with_present(present: i64) -> Self {
+        DeepTime { present }
+    }
+
+    /// Gets the present year
+    pub fn present(&self) -> i64 {
+        self.present
+    }
+
+    /// Converts a range of years, like [ScientificYear::range], to a range of years ago, most
+    /// recent first. For a single year, such as a [YYear](crate::level_1::YYear), pass `y..=y`.
+    /// Returns None on overflow.
+    ///
+    /// ```
+    /// use edtf::deep_time::DeepTime;
+    /// let deep = DeepTime::with_present(2000);
+    /// assert_eq!(deep.years_ago(-10_000..=-9_000), Some(11_000..=12_000));
+    /// ```
+    pub fn years_ago(&self, years: RangeInclusive<i64>) -> Option<RangeInclusive<i64>> {
+        let recent = self.present.checked_sub(*years.end())?;
+        let oldest = self.present.checked_sub(*years.start())?;
+        Some(recent..=oldest)
+    }
+
+    /// Converts a range of years, like [ScientificYear::range], to a range of `unit`s ago, most
+    /// recent first. Returns None on overflow.
+    pub fn in_units(
+        &self,
+        years: RangeInclusive<i64>,
+        unit: DeepTimeUnit,
+    ) -> Option<RangeInclusive<f64>> {
+        let ago = self.years_ago(years)?;
+        let scale = 10f64.powi(unit.exponent() as i32);
+        Some(*ago.start() as f64 / scale..=*ago.end() as f64 / scale)
+    }
+
+    /// Parses a deep time quantity like `66 Ma`, `2.5 Ga`, `12 ka BP` or `4,000 years ago` into
+    /// an [Edtf::Scientific].
+    ///
+    /// Every digit written after a decimal point is significant; trailing zeroes before it are
+    /// not, so `100 Ma` has one significant digit and `100.0 Ma` has four. Quantities ending in
+    /// `BP` are counted from 1950, and `b2k` from 2000, whatever the configured present is.
+    pub fn parse(&self, input: &str) -> Result<Edtf, ParseError> {
+        let mut tokens: Vec<&str> = input.split_whitespace().collect();
+        let present = match tokens.last().map(|t| t.to_ascii_lowercase()).as_deref() {
+            Some("bp") | Some("b.p.") => {
+                tokens.pop();
+                PRESENT
+            }
+            Some("b2k") => {
+                tokens.pop();
+                2000
+            }
+            Some("ago") => {
+                tokens.pop();
+                self.present
+            }
+            _ => self.present,
+        };
+        let (number, unit) = match tokens.as_slice() {
+            [number, unit] => (*number, *unit),
+            _ => return Err(ParseError::Invalid),
+        };
+        let unit = DeepTimeUnit::from_symbol(unit).ok_or(ParseError::Invalid)?;
+
+        let (int, frac) = match number.find('.') {
+            Some(ix) => (&number[..ix], Some(&number[ix + 1..])),
+            None => (number, None),
+        };
+        // validates the digits and any thousands separators
+        parse_number(int)?;
+        let mut digits: String = int.chars().filter(|&c| c != ',').collect();
+        let mut exponent = unit.exponent() as i64;
+        match frac {
+            Some(frac) => {
+                if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(ParseError::Invalid);
+                }
+                digits.push_str(frac);
+                exponent -= frac.len() as i64;
+            }
+            None => {
+                while digits.len() > 1 && digits.ends_with('0') {
+                    digits.pop();
+                    exponent += 1;
+                }
+            }
+        }
+        let digits = digits.trim_start_matches('0');
+        // no precision finer than a year, and nothing at all for zero
+        if digits.is_empty() || exponent < 0 {
+            return Err(ParseError::OutOfRange);
+        }
+        let exponent: u32 = exponent.try_into().map_err(|_| ParseError::OutOfRange)?;
+        let written: i64 = digits.parse().map_err(|_| ParseError::OutOfRange)?;
+        let tens = 10i64.checked_pow(exponent).ok_or(ParseError::OutOfRange)?;
+        let year = written
+            .checked_mul(tens)
+            .and_then(|ago| present.checked_sub(ago))
+            .ok_or(ParseError::OutOfRange)?;
+        let sig_digits = digits.len() as u32;
+        let year = round_to_digits(year, sig_digits).ok_or(ParseError::OutOfRange)?;
+        // the year 0 has no significant digits
+        if year == 0 {
+            return Err(ParseError::OutOfRange);
+        }
+        // write it with as many digits in the mantissa as are significant
+        let n = n_digits(year.checked_abs().ok_or(ParseError::OutOfRange)?);
+        let exponent = n.saturating_sub(sig_digits);
+        let mantissa = year / 10i64.pow(exponent);
+        ScientificYear::new_or_cal(mantissa, exponent as u16, sig_digits as u16)
+            .map(Edtf::Scientific)
+            .map_err(|_| ParseError::OutOfRange)
+    }
+}
+
+/// Rounds `year` to `sig_digits` significant digits, halves away from zero.
+fn round_to_digits(year: i64, sig_digits: u32) -> Option<i64> {
+    let n = n_digits(year.checked_abs()?);
+    if n <= sig_digits {
+        return Some(year);
+    }
+    let unit = 10i64.pow(n - sig_digits);
+    let (quotient, remainder) = (year / unit, year % unit);
+    let quotient = if remainder.abs() * 2 >= unit {
+        quotient + year.signum()
+    } else {
+        quotient
+    };
+    quotient.checked_mul(unit)
+}
+
+#[cfg(test)]
+fn scientific(edtf: Edtf) -> String {
+    match edtf {
+        Edtf::Scientific(s) => s.to_string(),
+        other => panic!("not a ScientificYear: {:?}", other),
+    }
+}
+
+#[test]
+fn deep_time_parse() {
+    let deep = DeepTime::default();
+    let parse = |s| deep.parse(s).map(scientific);
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(parse("66 Ma"), ok("Y-66E6S2"));
+    assert_eq!(parse("66 Myr"), ok("Y-66E6S2"));
+    assert_eq!(parse("66 mya"), ok("Y-66E6S2"));
+    assert_eq!(parse("2.5 Ga"), ok("Y-25E8S2"));
+    assert_eq!(parse("2.50 Ga"), ok("Y-250E7S3"));
+    assert_eq!(parse("0.5 Ma"), ok("Y-5E5S1"));
+    assert_eq!(parse("100 Ma"), ok("Y-1E8S1"));
+    assert_eq!(parse("100.0 Ma"), ok("Y-1000E5S4"));
+    assert_eq!(parse("12 kyr b2k"), ok("Y-10E3S2"));
+    assert_eq!(parse("4,000 years ago"), ok("Y-2E3S1"));
+    assert_eq!(parse("13.787 Ga"), ok("Y-13787E6S5"));
+    // no
+    assert_eq!(parse("66"), Err(ParseError::Invalid));
+    assert_eq!(parse("66 Xa"), Err(ParseError::Invalid));
+    assert_eq!(parse("6,6 Ma"), Err(ParseError::Invalid));
+    assert_eq!(parse("66. Ma"), Err(ParseError::Invalid));
+    assert_eq!(parse("-66 Ma"), Err(ParseError::Invalid));
+    assert_eq!(parse("0 Ma"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("1.2345 ka"), Err(ParseError::OutOfRange));
+    // the year 0 has no significant digits
+    assert_eq!(parse("1.95 ka"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("99999999999 Ga"), Err(ParseError::OutOfRange));
+}
+
+#[test]
+fn deep_time_present() {
+    let b2k = DeepTime::with_present(2000);
+    let parse = |s| b2k.parse(s).map(scientific);
+    assert_eq!(parse("12 ka"), Ok("Y-10E3S2".to_string()));
+    assert_eq!(parse("1.21 ka"), Ok("Y790S3".to_string()));
+    assert_eq!(parse("1.21 ka BP"), Ok("Y740S3".to_string()));
+    assert_eq!(b2k.years_ago(1000..=1999), Some(1..=1000));
+    assert_eq!(b2k.years_ago(i64::MIN..=0), None);
+    assert_eq!(
+        b2k.in_units(-4_000_000..=-3_000_001, DeepTimeUnit::Mega),
+        Some(3.002_001..=4.002)
+    );
+}
+
+#[test]
+fn deep_time_significant_digits() {
+    let deep = DeepTime::default();
+    let parse = |s| deep.parse(s).map(scientific);
+    let ok = |s: &str| Ok(s.to_string());
+    // the year 750, to as many digits as were written
+    assert_eq!(parse("1.2 ka"), ok("Y75E1S2"));
+    assert_eq!(parse("1.20 ka"), ok("Y750S3"));
+    // the year -10050, to two digits
+    assert_eq!(parse("12 ka BP"), ok("Y-10E3S2"));
+    // rounding up can add a digit
+    assert_eq!(round_to_digits(-99_998_050, 4), Some(-100_000_000));
+    assert_eq!(round_to_digits(-10_050, 2), Some(-10_000));
+    assert_eq!(round_to_digits(750, 3), Some(750));
+    assert_eq!(round_to_digits(i64::MIN, 1), None);
+}
@@ -0,0 +1,351 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Historical era years
+//!
+//! EDTF years are astronomical: there is a year zero, so `0000` is 1 BCE, `-0001` is 2 BCE, and
+//! so on. Historians count `1 BCE` straight to `1 CE`. [EraYear] converts between the two, and
+//! also handles years Before Present (BP), where the present is fixed at 1950.
+//!
+//! | Era year      | Astronomical / EDTF |
+//! | --------      | ------------------- |
+//! | `AD 79`       | `0079`              |
+//! | `1 BCE`       | `0000`              |
+//! | `44 BC`       | `-0043`             |
+//! | `10,000 BP`   | `-8050`             |
+//! | `12,345 BCE`  | `Y-12344`           |
+//! | `12,000 BCE`  | `Y-12E3S2`          |
+//!
+//! Years that fit in four digits become a [Date]. Longer years become a [YYear], unless they are
+//! a round number with at least three trailing zeroes, which is taken as an estimate and becomes
+//! a level 2 [ScientificYear] with that many significant digits. That avoids claiming that
+//! `10,000,000 BCE` means exactly `Y-9999999`.
+//!
+//! ```
+//! use edtf::era::{Era, EraEdtf, EraYear};
+//! use edtf::level_1::Date;
+//!
+//! let year = EraYear::parse("44 BC").unwrap();
+//! assert_eq!(year, EraYear::new(44, Era::Bc).unwrap());
+//! assert_eq!(year.to_astronomical(), Some(-43));
+//! assert_eq!(year.to_edtf(), Some(EraEdtf::Date(Date::from_year(-43))));
+//! assert_eq!(EraYear::from_astronomical(-43, Era::Bce).unwrap().to_string(), "44 BCE");
+//! ```
+
+use core::convert::TryInto;
+use core::fmt;
+
+use crate::helpers::inside_9999;
+use crate::level_1::{Date, YYear};
+use crate::level_2::ScientificYear;
+use crate::ParseError;
+
+/// The year BP counts back from.
+pub(crate) const PRESENT: i64 = 1950;
+
+/// An era, i.e. what a year is counted from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Era {
+    /// Before Common Era, the same years as [Era::Bc]
+    Bce,
+    /// Common Era, the same years as [Era::Ad]
+    Ce,
+    /// Before Christ
+    Bc,
+    /// Anno Domini
+    Ad,
+    /// Before Present, i.e. before 1950
+    Bp,
+}
+
+impl Era {
+    /// The era that counts backwards in this style, e.g. [Era::Bce] for [Era::Ce].
+    fn before(self) -> Self {
+        match self {
+            Era::Bce | Era::Ce => Era::Bce,
+            Era::Bc | Era::Ad => Era::Bc,
+            Era::Bp => Era::Bp,
+        }
+    }
+
+    /// The era that counts forwards in this style, e.g. [Era::Ce] for [Era::Bce].
+    fn after(self) -> Self {
+        match self {
+            Era::Bce | Era::Ce => Era::Ce,
+            Era::Bc | Era::Ad => Era::Ad,
+            Era::Bp => Era::Bp,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Era::Bce => "BCE",
+            Era::Ce => "CE",
+            Era::Bc => "BC",
+            Era::Ad => "AD",
+            Era::Bp => "BP",
+        }
+    }
+
+    pub(crate) fn from_abbreviation(s: &str) -> Option<Self> {
+        let letters: String = s
+            .chars()
+            .filter(|&c| c != '.')
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        Some(match letters.as_str() {
+            "BCE" => Era::Bce,
+            "CE" => Era::Ce,
+            "BC" => Era::Bc,
+            "AD" => Era::Ad,
+            "BP" => Era::Bp,
+            _ => return None,
+        })
+    }
+}
+
+/// A year counted in a historical era, like `44 BC` or `10,000 BP`. See the [module level
+/// documentation](crate::era).
+///
+/// The [Display](fmt::Display) implementation writes e.g. `44 BC`, `AD 79`, `79 CE` or
+/// `10000 BP`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct EraYear {
+    year: i64,
+    era: Era,
+}
+
+/// What an [EraYear] converts to, depending on its size. See the [module level
+/// documentation](crate::era).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EraEdtf {
+    /// A year in `-9999..=9999`
+    Date(Date),
+    /// An exact year outside `-9999..=9999`
+    YYear(YYear),
+    /// A round year outside `-9999..=9999`, read as an estimate
+    Scientific(ScientificYear),
+}
+
+impl EraYear {
+    /// Creates an era year. Returns None if `year` is less than 1, except that `0 BP` (1950) is
+    /// allowed.
+    pub fn new(year: i64, era: Era) -> Option<Self> {
+        let min = if era == Era::Bp { 0 } else { 1 };
+        if year < min {
+            return None;
+        }
+        Some(EraYear { year, era })
+    }
+
+    /// Gets the year, which is never negative.
+    pub fn year(&self) -> i64 {
+        self.year
+    }
+
+    /// Gets the era
+    pub fn era(&self) -> Era {
+        self.era
+    }
+
+    /// Converts an astronomical (EDTF) year to an era year, in the same style as `style`. So
+    /// `Era::Bce` and `Era::Ce` both give BCE/CE years, and `Era::Bc` and `Era::Ad` both give
+    /// BC/AD years.
+    ///
+    /// Returns None for years after 1950 in [Era::Bp], and on overflow.
+    ///
+    /// ```
+    /// use edtf::era::{Era, EraYear};
+    /// let ad = |y| EraYear::from_astronomical(y, Era::Ad).map(|e| e.to_string());
+    /// assert_eq!(ad(0).as_deref(), Some("1 BC"));
+    /// assert_eq!(ad(1).as_deref(), Some("AD 1"));
+    /// let bp = EraYear::from_astronomical(-8050, Era::Bp);
+    /// assert_eq!(bp, EraYear::new(10_000, Era::Bp));
+    /// ```
+    pub fn from_astronomical(year: i64, style: Era) -> Option<Self> {
+        if style == Era::Bp {
+            return Self::new(PRESENT.checked_sub(year)?, Era::Bp);
+        }
+        if year >= 1 {
+            Self::new(year, style.after())
+        } else {
+            Self::new(1i64.checked_sub(year)?, style.before())
+        }
+    }
+
+    /// Converts to an astronomical (EDTF) year. None on overflow.
+    pub fn to_astronomical(&self) -> Option<i64> {
+        match self.era {
+            Era::Ce | Era::Ad => Some(self.year),
+            Era::Bce | Era::Bc => 1i64.checked_sub(self.year),
+            Era::Bp => PRESENT.checked_sub(self.year),
+        }
+    }
+
+    /// Parses an era year like `100 BCE`, `44 B.C.`, `AD 79`, `79 ce` or `10,000 BP`. The era
+    /// may come before or after the number, and the number may use `,` as a thousands
+    /// separator.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut tokens = input.split_whitespace();
+        let (first, second) = match (tokens.next(), tokens.next(), tokens.next()) {
+            (Some(a), Some(b), None) => (a, b),
+            _ => return Err(ParseError::Invalid),
+        };
+        let (number, era) = match Era::from_abbreviation(second) {
+            Some(era) => (first, era),
+            None => (
+                second,
+                Era::from_abbreviation(first).ok_or(ParseError::Invalid)?,
+            ),
+        };
+        let year = parse_number(number)?;
+        Self::new(year, era).ok_or(ParseError::OutOfRange)
+    }
+
+    /// Converts to EDTF, choosing a [Date], [YYear] or [ScientificYear] depending on the size of
+    /// the year. None on overflow.
+    ///
+    /// ```
+    /// use edtf::era::{EraEdtf, EraYear};
+    /// let edtf = |s| EraYear::parse(s).unwrap().to_edtf().unwrap().to_string();
+    /// assert_eq!(edtf("10,000 BP"), "-8050");
+    /// assert_eq!(edtf("12,345 BCE"), "Y-12344");
+    /// assert_eq!(edtf("66,000,000 BP"), "Y-66E6S2");
+    /// ```
+    pub fn to_edtf(&self) -> Option<EraEdtf> {
+        let astro = self.to_astronomical()?;
+        if inside_9999(astro) {
+            return Date::from_ymd_opt(astro.try_into().ok()?, 0, 0).map(EraEdtf::Date);
+        }
+        let mut zeroes = 0u32;
+        while zeroes < 18 && self.year % 10i64.pow(zeroes + 1) == 0 {
+            zeroes += 1;
+        }
+        if zeroes < 3 {
+            return YYear::new_opt(astro).map(EraEdtf::YYear);
+        }
+        // round the astronomical year to the same number of significant digits as the era year
+        let tens = 10i64.pow(zeroes);
+        let mantissa = astro.checked_add(tens / 2)?.div_euclid(tens);
+        let sig_digits = n_digits(self.year) - zeroes;
+        ScientificYear::new_or_cal(mantissa, zeroes as u16, sig_digits as u16)
+            .ok()
+            .map(EraEdtf::Scientific)
+    }
+}
+
+pub(crate) fn n_digits(mut n: i64) -> u32 {
+    let mut count = 1;
+    while n >= 10 {
+        n /= 10;
+        count += 1;
+    }
+    count
+}
+
+/// `10000` or `10,000`
+pub(crate) fn parse_number(s: &str) -> Result<i64, ParseError> {
+    let mut groups = s.split(',');
+    let first = groups.next().unwrap_or("");
+    let valid_group = |g: &str, max: usize| {
+        !g.is_empty() && g.len() <= max && g.bytes().all(|b| b.is_ascii_digit())
+    };
+    if !valid_group(first, if s.contains(',') { 3 } else { usize::MAX }) {
+        return Err(ParseError::Invalid);
+    }
+    let mut digits = first.to_string();
+    for group in groups {
+        if group.len() != 3 || !valid_group(group, 3) {
+            return Err(ParseError::Invalid);
+        }
+        digits.push_str(group);
+    }
+    digits.parse().map_err(|_| ParseError::OutOfRange)
+}
+
+impl fmt::Display for EraYear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.era {
+            Era::Ad => write!(f, "{} {}", self.era.as_str(), self.year),
+            _ => write!(f, "{} {}", self.year, self.era.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for EraEdtf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EraEdtf::Date(d) => write!(f, "{}", d),
+            EraEdtf::YYear(y) => write!(f, "{}", y),
+            EraEdtf::Scientific(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[test]
+fn era_parse() {
+    let parse = |s: &str| EraYear::parse(s).map(|e| (e.year(), e.era()));
+    assert_eq!(parse("100 BCE"), Ok((100, Era::Bce)));
+    assert_eq!(parse("100 B.C.E."), Ok((100, Era::Bce)));
+    assert_eq!(parse("44 BC"), Ok((44, Era::Bc)));
+    assert_eq!(parse("44 b.c."), Ok((44, Era::Bc)));
+    assert_eq!(parse("AD 79"), Ok((79, Era::Ad)));
+    assert_eq!(parse("79 AD"), Ok((79, Era::Ad)));
+    assert_eq!(parse("79 CE"), Ok((79, Era::Ce)));
+    assert_eq!(parse("10,000 BP"), Ok((10_000, Era::Bp)));
+    assert_eq!(parse("0 BP"), Ok((0, Era::Bp)));
+    assert_eq!(parse("1,234,567 BCE"), Ok((1_234_567, Era::Bce)));
+    // no
+    assert_eq!(parse("0 BCE"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("100"), Err(ParseError::Invalid));
+    assert_eq!(parse("100 BCE BCE"), Err(ParseError::Invalid));
+    assert_eq!(parse("100 XYZ"), Err(ParseError::Invalid));
+    assert_eq!(parse("-100 BCE"), Err(ParseError::Invalid));
+    assert_eq!(parse("10,00 BP"), Err(ParseError::Invalid));
+    assert_eq!(parse("1000,000 BP"), Err(ParseError::Invalid));
+    assert_eq!(parse(",000 BP"), Err(ParseError::Invalid));
+    assert_eq!(
+        parse("99999999999999999999 BP"),
+        Err(ParseError::OutOfRange)
+    );
+}
+
+#[test]
+fn era_astronomical() {
+    let astro = |y, era| EraYear::new(y, era).unwrap().to_astronomical();
+    assert_eq!(astro(1, Era::Ce), Some(1));
+    assert_eq!(astro(1, Era::Bce), Some(0));
+    assert_eq!(astro(44, Era::Bc), Some(-43));
+    assert_eq!(astro(0, Era::Bp), Some(1950));
+    assert_eq!(astro(10_000, Era::Bp), Some(-8050));
+    let from = |y, era| EraYear::from_astronomical(y, era).map(|e| e.to_string());
+    assert_eq!(from(0, Era::Ce).as_deref(), Some("1 BCE"));
+    assert_eq!(from(-43, Era::Ad).as_deref(), Some("44 BC"));
+    assert_eq!(from(79, Era::Bc).as_deref(), Some("AD 79"));
+    assert_eq!(from(79, Era::Bce).as_deref(), Some("79 CE"));
+    assert_eq!(from(1950, Era::Bp).as_deref(), Some("0 BP"));
+    assert_eq!(from(1951, Era::Bp), None);
+    assert_eq!(from(i64::MIN, Era::Bce), None);
+}
+
+#[test]
+fn era_to_edtf() {
+    let edtf = |s: &str| EraYear::parse(s).unwrap().to_edtf().map(|e| e.to_string());
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(edtf("100 BCE"), some("-0099"));
+    assert_eq!(edtf("AD 79"), some("0079"));
+    assert_eq!(edtf("1 BC"), some("0000"));
+    assert_eq!(edtf("10,000 BCE"), some("-9999"));
+    assert_eq!(edtf("10,001 BCE"), some("Y-10000"));
+    assert_eq!(edtf("12,345 BCE"), some("Y-12344"));
+    assert_eq!(edtf("12,300 BCE"), some("Y-12299"));
+    assert_eq!(edtf("12,000 BCE"), some("Y-12E3S2"));
+    assert_eq!(edtf("10,000,000 BCE"), some("Y-1E7S1"));
+    assert_eq!(edtf("66,000,000 BP"), some("Y-66E6S2"));
+    assert_eq!(edtf("12,000 BP"), some("Y-10E3S2"));
+    assert_eq!(edtf("20,000 CE"), some("Y2E4S1"));
+    assert_eq!(edtf("12,345 AD"), some("Y12345"));
+}
@@ -0,0 +1,260 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Fiscal and academic years
+//!
+//! Financial, government and school records are often dated by a year that doesn't start on 1
+//! January, like `FY2019` or `AY 2019-20`. A [FiscalYearConvention] says when those years start
+//! and how they are named, and converts between them and EDTF.
+//!
+//! ```
+//! use edtf::fiscal::FiscalYearConvention;
+//! use edtf::level_1::Edtf;
+//!
+//! let us = FiscalYearConvention::US_FEDERAL;
+//! assert_eq!(us.parse("FY2019"), Edtf::parse("2018-10-01/2019-09-30"));
+//! assert_eq!(us.parse("FY 2018-19"), Edtf::parse("2018-10-01/2019-09-30"));
+//!
+//! let edtf = Edtf::parse("2019-09/2019-10").unwrap();
+//! assert_eq!(us.fiscal_years(&edtf), Some(2019..=2020));
+//! ```
+
+use core::ops::RangeInclusive;
+
+use crate::level_1::{Date, Edtf};
+use crate::{DateComplete, ParseError};
+
+/// Which calendar year a fiscal year is named after, when it is named with one year.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FiscalYearName {
+    /// The year it starts in, e.g. UK tax years
+    StartYear,
+    /// The year it ends in, e.g. US federal fiscal years
+    EndYear,
+}
+
+/// When fiscal years start, and how they are named. See the [module level
+/// documentation](crate::fiscal).
+///
+/// Labels with two years, like `2019-20`, always name the year starting in the first of them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FiscalYearConvention {
+    start_month: u8,
+    start_day: u8,
+    naming: FiscalYearName,
+}
+
+impl FiscalYearConvention {
+    /// The US federal government's fiscal year, from 1 October, named after the year it ends.
+    pub const US_FEDERAL: Self = FiscalYearConvention {
+        start_month: 10,
+        start_day: 1,
+        naming: FiscalYearName::EndYear,
+    };
+
+    /// The UK tax year, from 6 April, named after the year it starts.
+    pub const UK_TAX: Self = FiscalYearConvention {
+        start_month: 4,
+        start_day: 6,
+        naming: FiscalYearName::StartYear,
+    };
+
+    /// The Australian financial year, from 1 July, named after the year it ends.
+    pub const AUSTRALIA: Self = FiscalYearConvention {
+        start_month: 7,
+        start_day: 1,
+        naming: FiscalYearName::EndYear,
+    };
+
+    /// A fiscal year starting on the given day of the year, such as 1 September for a typical
+    /// academic year. Returns None for days that don't exist, and for 29 February.
+    pub fn with_year_start(month: u32, day: u32, naming: FiscalYearName) -> Option<Self> {
+        // 1 is not a leap year
+        DateComplete::from_ymd_opt(1, month, day)?;
+        Some(FiscalYearConvention {
+            start_month: month as u8,
+            start_day: day as u8,
+            naming,
+        })
+    }
+
+    /// Gets the month and day fiscal years start on
+    pub fn start(&self) -> (u32, u32) {
+        (self.start_month as u32, self.start_day as u32)
+    }
+
+    /// Gets how fiscal years are named
+    pub fn naming(&self) -> FiscalYearName {
+        self.naming
+    }
+
+    fn starts_on_jan_1(&self) -> bool {
+        (self.start_month, self.start_day) == (1, 1)
+    }
+
+    /// The difference between a fiscal year's name and the calendar year it starts in.
+    fn name_offset(&self) -> i32 {
+        match self.naming {
+            FiscalYearName::EndYear if !self.starts_on_jan_1() => 1,
+            _ => 0,
+        }
+    }
+
+    fn start_in(&self, year: i32) -> Option<DateComplete> {
+        DateComplete::from_ymd_opt(year, self.start_month as u32, self.start_day as u32)
+    }
+
+    /// The first and last days of a fiscal year. None on overflow.
+    pub fn days(&self, fiscal_year: i32) -> Option<(DateComplete, DateComplete)> {
+        let start_year = fiscal_year.checked_sub(self.name_offset())?;
+        let first = self.start_in(start_year)?;
+        let last = self.start_in(start_year.checked_add(1)?)?.add_days(-1)?;
+        Some((first, last))
+    }
+
+    /// A fiscal year as an interval of days. None on overflow.
+    ///
+    /// ```
+    /// use edtf::fiscal::FiscalYearConvention;
+    /// let uk = FiscalYearConvention::UK_TAX;
+    /// assert_eq!(uk.to_edtf(2019).unwrap().to_string(), "2019-04-06/2020-04-05");
+    /// ```
+    pub fn to_edtf(&self, fiscal_year: i32) -> Option<Edtf> {
+        let (first, last) = self.days(fiscal_year)?;
+        Some(Edtf::Interval(
+            Date::from_complete(first),
+            Date::from_complete(last),
+        ))
+    }
+
+    /// The fiscal year a day falls in. None on overflow.
+    pub fn fiscal_year_of(&self, date: DateComplete) -> Option<i32> {
+        let (m, d) = (date.month(), date.day());
+        let before_start = ((m, d) < self.start()) as i32;
+        date.year().checked_add(self.name_offset() - before_start)
+    }
+
+    /// The fiscal years an EDTF date or interval overlaps. None if it is open-ended or
+    /// unknown at either end, or for a [Edtf::YYear] too large to count in days, or on
+    /// overflow.
+    pub fn fiscal_years(&self, edtf: &Edtf) -> Option<RangeInclusive<i32>> {
+        let (first, last) = edtf.bounds();
+        let first = self.fiscal_year_of(first.day()?)?;
+        let last = self.fiscal_year_of(last.day()?)?;
+        Some(first..=last)
+    }
+
+    /// Parses a fiscal year label and converts it to an interval of days. Accepts an optional
+    /// `FY`, `AY` or `SY` prefix, then either one year, named according to the convention, or
+    /// two consecutive years, like `2019-20`, `2019/20` or `2019–2020`.
+    pub fn parse(&self, input: &str) -> Result<Edtf, ParseError> {
+        let input = input.trim();
+        let prefix = input.get(..2).map(|p| p.to_ascii_uppercase());
+        let label = match prefix.as_deref() {
+            Some("FY") | Some("AY") | Some("SY") => input[2..].trim_start(),
+            _ => input,
+        };
+        let fiscal_year = match parse_years(label)? {
+            (year, None) => year,
+            (year, Some(second)) => {
+                if self.starts_on_jan_1() {
+                    return Err(ParseError::Invalid);
+                }
+                let modulus = 10i32.pow(second.len() as u32);
+                let second: i32 = second.parse().map_err(|_| ParseError::Invalid)?;
+                if second != (year + 1) % modulus {
+                    return Err(ParseError::Invalid);
+                }
+                year + self.name_offset()
+            }
+        };
+        self.to_edtf(fiscal_year).ok_or(ParseError::OutOfRange)
+    }
+}
+
+/// `2019`, or `2019` and the digits after a separator in `2019-20`, `2019/20` or `2019–2020`.
+fn parse_years(s: &str) -> Result<(i32, Option<&str>), ParseError> {
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let (first, second) = match s.find(['-', '/', '–']) {
+        Some(ix) => {
+            let sep = s[ix..].chars().next().map_or(1, char::len_utf8);
+            (&s[..ix], Some(&s[ix + sep..]))
+        }
+        None => (s, None),
+    };
+    if first.len() != 4 || !digits(first) {
+        return Err(ParseError::Invalid);
+    }
+    if let Some(second) = second {
+        if !(second.len() == 2 || second.len() == 4) || !digits(second) {
+            return Err(ParseError::Invalid);
+        }
+    }
+    let year = first.parse().map_err(|_| ParseError::Invalid)?;
+    Ok((year, second))
+}
+
+#[test]
+fn fiscal_labels() {
+    let parse = |c: FiscalYearConvention, s| c.parse(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    let us = FiscalYearConvention::US_FEDERAL;
+    assert_eq!(parse(us, "FY2019"), ok("2018-10-01/2019-09-30"));
+    assert_eq!(parse(us, "fy 2019"), ok("2018-10-01/2019-09-30"));
+    assert_eq!(parse(us, "2019"), ok("2018-10-01/2019-09-30"));
+    assert_eq!(parse(us, "FY2018-19"), ok("2018-10-01/2019-09-30"));
+    assert_eq!(parse(us, "FY2018/2019"), ok("2018-10-01/2019-09-30"));
+    assert_eq!(parse(us, "FY2018–19"), ok("2018-10-01/2019-09-30"));
+    let uk = FiscalYearConvention::UK_TAX;
+    assert_eq!(parse(uk, "2019"), ok("2019-04-06/2020-04-05"));
+    assert_eq!(parse(uk, "2019-20"), ok("2019-04-06/2020-04-05"));
+    assert_eq!(parse(uk, "1999-00"), ok("1999-04-06/2000-04-05"));
+    let academic = FiscalYearConvention::with_year_start(9, 1, FiscalYearName::StartYear).unwrap();
+    assert_eq!(parse(academic, "AY 2019-20"), ok("2019-09-01/2020-08-31"));
+    assert_eq!(parse(academic, "AY2019"), ok("2019-09-01/2020-08-31"));
+    let calendar = FiscalYearConvention::with_year_start(1, 1, FiscalYearName::EndYear).unwrap();
+    assert_eq!(parse(calendar, "FY2019"), ok("2019-01-01/2019-12-31"));
+    // no
+    assert_eq!(parse(calendar, "2019-20"), Err(ParseError::Invalid));
+    assert_eq!(parse(us, "FY2019-21"), Err(ParseError::Invalid));
+    assert_eq!(parse(us, "FY19"), Err(ParseError::Invalid));
+    assert_eq!(parse(us, "FY2019-2"), Err(ParseError::Invalid));
+    assert_eq!(parse(us, "XY2019"), Err(ParseError::Invalid));
+    assert_eq!(parse(us, "FY2019-"), Err(ParseError::Invalid));
+    assert_eq!(
+        FiscalYearConvention::with_year_start(2, 29, FiscalYearName::StartYear),
+        None
+    );
+    assert_eq!(
+        FiscalYearConvention::with_year_start(13, 1, FiscalYearName::StartYear),
+        None
+    );
+}
+
+#[test]
+fn fiscal_years_of() {
+    let ymd = DateComplete::from_ymd;
+    let us = FiscalYearConvention::US_FEDERAL;
+    assert_eq!(us.fiscal_year_of(ymd(2019, 9, 30)), Some(2019));
+    assert_eq!(us.fiscal_year_of(ymd(2019, 10, 1)), Some(2020));
+    let uk = FiscalYearConvention::UK_TAX;
+    assert_eq!(uk.fiscal_year_of(ymd(2020, 4, 5)), Some(2019));
+    assert_eq!(uk.fiscal_year_of(ymd(2020, 4, 6)), Some(2020));
+    // the year before i32::MIN, or after i32::MAX
+    assert_eq!(us.fiscal_year_of(ymd(i32::MAX, 12, 31)), None);
+    assert_eq!(uk.fiscal_year_of(ymd(i32::MIN, 1, 1)), None);
+    assert_eq!(us.fiscal_year_of(ymd(i32::MIN, 1, 1)), Some(i32::MIN));
+    assert_eq!(uk.fiscal_year_of(ymd(i32::MAX, 12, 31)), Some(i32::MAX));
+
+    let years = |c: FiscalYearConvention, s| c.fiscal_years(&Edtf::parse(s).unwrap());
+    assert_eq!(years(us, "2019"), Some(2019..=2020));
+    assert_eq!(years(us, "2019-05-01/2019-09-30"), Some(2019..=2019));
+    assert_eq!(years(uk, "2019-21"), Some(2018..=2019));
+    assert_eq!(years(uk, "2019-04-06T12:00:00Z"), Some(2019..=2019));
+    assert_eq!(years(uk, "2019/.."), None);
+    assert_eq!(years(us, "Y20000"), Some(20000..=20001));
+    assert_eq!(years(us, "Y-17000000000"), None);
+}
@@ -0,0 +1,510 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # iCalendar (RFC 5545) `DTSTART` / `DTEND`
+//!
+//! Maps a [level_1::Edtf][Edtf] onto the date properties of a `VEVENT`, and back again.
+//!
+//! - A date or interval becomes an all-day event, `DTSTART;VALUE=DATE` and `DTEND;VALUE=DATE`.
+//!   As RFC 5545 requires, `DTEND` is *exclusive*, so `2019` runs from `20190101` to `20200101`.
+//!   Unspecified digits are widened to the days they could refer to, so `2019-XX` is also the
+//!   whole of 2019.
+//! - A [DateTime] becomes a single `DTSTART`. Times with [TzOffset::Utc] are written in UTC
+//!   (`...Z`), and times with [TzOffset::Unspecified] are written as floating times. iCalendar
+//!   cannot express a fixed offset like `+04:00` without a `VTIMEZONE`, so those are converted to
+//!   UTC.
+//! - iCalendar has no notion of uncertainty, so any [Certainty] other than `Certain` is written
+//!   as an `X-EDTF-DTSTART-CERTAINTY` or `X-EDTF-DTEND-CERTAINTY` property, and read back when
+//!   parsing.
+//!
+//! Parsing a `VEVENT` produces the most compact EDTF for the range of days it covers, so
+//! `2019-01-01/2019-12-31` comes back as `2019`. Parsing cannot recover unspecified digits.
+//!
+//! ```
+//! use edtf::level_1::Edtf;
+//! use edtf::ical::VEventDates;
+//!
+//! let edtf = Edtf::parse("2019-06~").unwrap();
+//! let dates = edtf.to_ical().unwrap();
+//! assert_eq!(
+//!     dates.to_string(),
+//!     "DTSTART;VALUE=DATE:20190601\r\n\
+//!      DTEND;VALUE=DATE:20190701\r\n\
+//!      X-EDTF-DTSTART-CERTAINTY:APPROXIMATE\r\n"
+//! );
+//!
+//! let parsed = VEventDates::parse(&dates.to_string()).unwrap();
+//! assert_eq!(parsed.to_edtf(), edtf);
+//! ```
+
+use core::fmt;
+
+#[allow(unused_imports)]
+use nom::{
+    branch as nb, bytes::complete as nbc, character as nch, character::complete as ncc,
+    combinator as nc, error::ParseError as NomParseError, sequence as ns, Finish, IResult, ParseTo,
+    Parser,
+};
+
+use crate::common::{is_valid_complete_date, two_digits, year_n, StrResult};
+use crate::helpers::ParserExt;
+use crate::level_1::{Certainty, Date, Edtf};
+use crate::{DateComplete, DateTime, ParseError, Time, TzOffset};
+
+const X_DTSTART_CERTAINTY: &str = "X-EDTF-DTSTART-CERTAINTY";
+const X_DTEND_CERTAINTY: &str = "X-EDTF-DTEND-CERTAINTY";
+
+/// The value of a `DTSTART` or `DTEND` property.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ICalValue {
+    /// `DTSTART;VALUE=DATE:20190715`
+    Date(DateComplete),
+    /// `DTSTART:20190715T015600Z`, or without the `Z` for a floating time. The offset is always
+    /// [TzOffset::Utc] or [TzOffset::Unspecified].
+    DateTime(DateTime),
+}
+
+/// The date properties of a `VEVENT`. See the [module level documentation](crate::ical).
+///
+/// The [Display](fmt::Display) implementation writes one content line per property, each
+/// terminated by CRLF.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VEventDates {
+    start: ICalValue,
+    end: Option<ICalValue>,
+    start_certainty: Certainty,
+    end_certainty: Certainty,
+}
+
+impl VEventDates {
+    /// The `DTSTART` property.
+    pub fn start(&self) -> ICalValue {
+        self.start
+    }
+
+    /// The `DTEND` property, which is exclusive. Not present for date-times.
+    pub fn end(&self) -> Option<ICalValue> {
+        self.end
+    }
+
+    /// The certainty of the start, from `X-EDTF-DTSTART-CERTAINTY`.
+    pub fn start_certainty(&self) -> Certainty {
+        self.start_certainty
+    }
+
+    /// The certainty of the end, from `X-EDTF-DTEND-CERTAINTY`.
+    pub fn end_certainty(&self) -> Certainty {
+        self.end_certainty
+    }
+
+    /// Parses the date properties out of a `VEVENT`. The input can be a whole iCalendar
+    /// object, in which case the first `VEVENT` is used, or just some content lines. Other
+    /// properties are ignored. Long lines may be folded.
+    ///
+    /// `DTSTART` is required. A `DTSTART` with a `TZID` parameter is read as a floating time,
+    /// because time zone definitions are out of scope. A `DTEND` before the `DTSTART` returns
+    /// [ParseError::OutOfRange].
+    ///
+    /// ```
+    /// use edtf::ical::VEventDates;
+    /// let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nSUMMARY:Armistice\r\n\
+    ///            DTSTART;VALUE=DATE:19181111\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    /// let dates = VEventDates::parse(ics).unwrap();
+    /// assert_eq!(dates.to_edtf().to_string(), "1918-11-11");
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let unfolded = input
+            .replace("\r\n ", "")
+            .replace("\r\n\t", "")
+            .replace("\n ", "")
+            .replace("\n\t", "");
+        let lines: Vec<&str> = unfolded.lines().map(|l| l.trim_end_matches('\r')).collect();
+        let lines = match lines
+            .iter()
+            .position(|l| l.eq_ignore_ascii_case("BEGIN:VEVENT"))
+        {
+            Some(begin) => {
+                let rest = &lines[begin + 1..];
+                let end = rest
+                    .iter()
+                    .position(|l| l.eq_ignore_ascii_case("END:VEVENT"))
+                    .unwrap_or(rest.len());
+                &rest[..end]
+            }
+            None => &lines[..],
+        };
+
+        let mut start = None;
+        let mut end = None;
+        let mut start_certainty = Certainty::Certain;
+        let mut end_certainty = Certainty::Certain;
+        for line in lines {
+            let ContentLine {
+                name,
+                is_date,
+                value,
+            } = match ContentLine::split(line) {
+                Some(cl) => cl,
+                None => continue,
+            };
+            if name.eq_ignore_ascii_case("DTSTART") {
+                start = Some(ICalValue::parse(value, is_date)?);
+            } else if name.eq_ignore_ascii_case("DTEND") {
+                end = Some(ICalValue::parse(value, is_date)?);
+            } else if name.eq_ignore_ascii_case(X_DTSTART_CERTAINTY) {
+                start_certainty = parse_certainty(value)?;
+            } else if name.eq_ignore_ascii_case(X_DTEND_CERTAINTY) {
+                end_certainty = parse_certainty(value)?;
+            }
+        }
+        let start = start.ok_or(ParseError::Invalid)?;
+        match (start, end) {
+            (ICalValue::Date(s), Some(ICalValue::Date(e))) if s >= e => {
+                return Err(ParseError::OutOfRange)
+            }
+            (ICalValue::DateTime(s), Some(ICalValue::DateTime(e))) => {
+                let instant = |dt: DateTime| (dt.date, dt.time.hh, dt.time.mm, dt.time.ss);
+                if instant(e) < instant(s) {
+                    return Err(ParseError::OutOfRange);
+                }
+            }
+            (ICalValue::Date(_), Some(ICalValue::DateTime(_)))
+            | (ICalValue::DateTime(_), Some(ICalValue::Date(_))) => {
+                return Err(ParseError::Invalid)
+            }
+            _ => {}
+        }
+        Ok(VEventDates {
+            start,
+            end,
+            start_certainty,
+            end_certainty,
+        })
+    }
+
+    /// Converts to a level 1 EDTF.
+    ///
+    /// An all-day event becomes the most compact [Edtf] covering the same days, with the
+    /// certainty from the `X-EDTF-*` properties applied. A date-time without a `DTEND`, or whose
+    /// `DTEND` is on the same day, becomes an [Edtf::DateTime]; otherwise it becomes an interval
+    /// of the two days, as level 1 has no intervals of date-times.
+    pub fn to_edtf(&self) -> Edtf {
+        let (first, last) = match (self.start, self.end) {
+            (ICalValue::Date(s), Some(ICalValue::Date(e))) => (s, e.add_days(-1).unwrap_or(s)),
+            (ICalValue::Date(s), _) => (s, s),
+            (ICalValue::DateTime(s), Some(ICalValue::DateTime(e))) if e.date != s.date => {
+                (s.date, e.date)
+            }
+            (ICalValue::DateTime(s), _) => return Edtf::DateTime(s),
+        };
+        let sc = self.start_certainty;
+        let ec = self.end_certainty;
+        // iCalendar years are four digits, so they are always in range.
+        let edtf = Edtf::from_day_range(first, last).unwrap_or_else(|| {
+            Edtf::Interval(Date::from_complete(first), Date::from_complete(last))
+        });
+        match edtf {
+            Edtf::Date(d) if ec == Certainty::Certain || ec == sc => {
+                Edtf::Date(d.and_certainty(sc))
+            }
+            Edtf::Date(d) => Edtf::Interval(d.and_certainty(sc), d.and_certainty(ec)),
+            Edtf::Interval(a, b) => Edtf::Interval(a.and_certainty(sc), b.and_certainty(ec)),
+            other => other,
+        }
+    }
+}
+
+struct ContentLine<'a> {
+    name: &'a str,
+    /// Whether there was a `VALUE=DATE` parameter
+    is_date: bool,
+    value: &'a str,
+}
+
+impl<'a> ContentLine<'a> {
+    /// `name *(";" param) ":" value`. Parameter values may be quoted, and quoted values may
+    /// contain colons.
+    fn split(line: &'a str) -> Option<Self> {
+        let mut in_quotes = false;
+        let colon = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                None
+            }
+            ':' if !in_quotes => Some(i),
+            _ => None,
+        })?;
+        let (head, value) = (&line[..colon], &line[colon + 1..]);
+        let mut parts = head.split(';');
+        let name = parts.next()?;
+        let is_date = parts.any(|p| p.eq_ignore_ascii_case("VALUE=DATE"));
+        Some(ContentLine {
+            name,
+            is_date,
+            value,
+        })
+    }
+}
+
+impl ICalValue {
+    fn parse(value: &str, is_date: bool) -> Result<Self, ParseError> {
+        let (_, ((year, month, day), time)) = ical_date
+            .and(ns::preceded(ncc::char('T'), ical_time).optional())
+            .complete()
+            .parse(value.trim())
+            .map_err(|_| ParseError::Invalid)?;
+        let date = is_valid_complete_date(year, month, day)?;
+        match time {
+            None => Ok(ICalValue::Date(date)),
+            Some(_) if is_date => Err(ParseError::Invalid),
+            Some((hh, mm, ss, utc)) => {
+                let tz = if utc {
+                    TzOffset::Utc
+                } else {
+                    TzOffset::Unspecified
+                };
+                let time = Time::from_hmsz_opt(hh as u32, mm as u32, ss as u32, tz)
+                    .ok_or(ParseError::OutOfRange)?;
+                Ok(ICalValue::DateTime(DateTime { date, time }))
+            }
+        }
+    }
+}
+
+/// `YYYYMMDD`
+fn ical_date(remain: &str) -> StrResult<'_, (i32, u8, u8)> {
+    year_n(4)
+        .and(two_digits::<u8>)
+        .and(two_digits::<u8>)
+        .map(|((y, m), d)| (y, m, d))
+        .parse(remain)
+}
+
+/// `HHMMSS` with an optional `Z`
+fn ical_time(remain: &str) -> StrResult<'_, (u8, u8, u8, bool)> {
+    two_digits::<u8>
+        .and(two_digits::<u8>)
+        .and(two_digits::<u8>)
+        .and(ncc::char('Z').optional())
+        .map(|(((hh, mm), ss), z)| (hh, mm, ss, z.is_some()))
+        .parse(remain)
+}
+
+fn parse_certainty(value: &str) -> Result<Certainty, ParseError> {
+    Ok(match value.trim() {
+        v if v.eq_ignore_ascii_case("CERTAIN") => Certainty::Certain,
+        v if v.eq_ignore_ascii_case("UNCERTAIN") => Certainty::Uncertain,
+        v if v.eq_ignore_ascii_case("APPROXIMATE") => Certainty::Approximate,
+        v if v.eq_ignore_ascii_case("APPROXIMATE-UNCERTAIN") => Certainty::ApproximateUncertain,
+        _ => return Err(ParseError::Invalid),
+    })
+}
+
+fn certainty_str(certainty: Certainty) -> Option<&'static str> {
+    match certainty {
+        Certainty::Certain => None,
+        Certainty::Uncertain => Some("UNCERTAIN"),
+        Certainty::Approximate => Some("APPROXIMATE"),
+        Certainty::ApproximateUncertain => Some("APPROXIMATE-UNCERTAIN"),
+    }
+}
+
+/// iCalendar years are exactly four digits.
+fn in_ical_range(date: DateComplete) -> bool {
+    (0..=9999).contains(&date.year())
+}
+
+impl fmt::Display for ICalValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let date = match self {
+            ICalValue::Date(d) => d,
+            ICalValue::DateTime(dt) => &dt.date,
+        };
+        write!(f, "{:04}{:02}{:02}", date.year, date.month, date.day)?;
+        if let ICalValue::DateTime(dt) = self {
+            let Time { hh, mm, ss, tz } = dt.time;
+            write!(f, "T{:02}{:02}{:02}", hh, mm, ss)?;
+            if tz == TzOffset::Utc {
+                write!(f, "Z")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for VEventDates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let param = |v: &ICalValue| match v {
+            ICalValue::Date(_) => ";VALUE=DATE",
+            ICalValue::DateTime(_) => "",
+        };
+        write!(f, "DTSTART{}:{}\r\n", param(&self.start), self.start)?;
+        if let Some(end) = &self.end {
+            write!(f, "DTEND{}:{}\r\n", param(end), end)?;
+        }
+        if let Some(c) = certainty_str(self.start_certainty) {
+            write!(f, "{}:{}\r\n", X_DTSTART_CERTAINTY, c)?;
+        }
+        if let Some(c) = certainty_str(self.end_certainty) {
+            write!(f, "{}:{}\r\n", X_DTEND_CERTAINTY, c)?;
+        }
+        Ok(())
+    }
+}
+
+/// # iCalendar
+impl Edtf {
+    /// Converts to `VEVENT` date properties. See the [crate::ical] module documentation.
+    ///
+    /// Returns None for open or unknown interval terminals and `Y`-years, which iCalendar cannot
+    /// express, and for any dates outside the years `0000..=9999`.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let dates = Edtf::parse("2019-08-17T23:59:30+10:00").unwrap().to_ical().unwrap();
+    /// assert_eq!(dates.to_string(), "DTSTART:20190817T135930Z\r\n");
+    /// let dates = Edtf::parse("2019-08-17T23:59:30").unwrap().to_ical().unwrap();
+    /// assert_eq!(dates.to_string(), "DTSTART:20190817T235930\r\n");
+    /// ```
+    pub fn to_ical(&self) -> Option<VEventDates> {
+        let (start, end) = match *self {
+            Edtf::Date(d) => (d, d),
+            Edtf::Interval(a, b) => (a, b),
+            Edtf::DateTime(dt) => {
+                let dt = match dt.time.tz {
+                    TzOffset::Unspecified | TzOffset::Utc => dt,
+                    TzOffset::Hours(_) | TzOffset::Minutes(_) => dt.to_utc_opt()?,
+                };
+                if !in_ical_range(dt.date) {
+                    return None;
+                }
+                return Some(VEventDates {
+                    start: ICalValue::DateTime(dt),
+                    end: None,
+                    start_certainty: Certainty::Certain,
+                    end_certainty: Certainty::Certain,
+                });
+            }
+            Edtf::IntervalFrom(..) | Edtf::IntervalTo(..) | Edtf::YYear(_) => return None,
+        };
+        let first = start.first_day();
+        let end_exclusive = end.last_day().add_days(1)?;
+        if !in_ical_range(first) || !in_ical_range(end_exclusive) {
+            return None;
+        }
+        let end_certainty = match self {
+            Edtf::Interval(..) => end.certainty(),
+            _ => Certainty::Certain,
+        };
+        Some(VEventDates {
+            start: ICalValue::Date(first),
+            end: Some(ICalValue::Date(end_exclusive)),
+            start_certainty: start.certainty(),
+            end_certainty,
+        })
+    }
+
+    /// Parses the date properties of a `VEVENT` into an EDTF. Shorthand for
+    /// [VEventDates::parse] followed by [VEventDates::to_edtf].
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let edtf = Edtf::from_ical("DTSTART;VALUE=DATE:20190101\r\nDTEND;VALUE=DATE:20210101\r\n");
+    /// assert_eq!(edtf, Edtf::parse("2019/2020"));
+    /// ```
+    pub fn from_ical(input: &str) -> Result<Self, ParseError> {
+        VEventDates::parse(input).map(|v| v.to_edtf())
+    }
+}
+
+#[cfg(test)]
+fn roundtrip(edtf: &str) -> String {
+    let ical = Edtf::parse(edtf).unwrap().to_ical().unwrap().to_string();
+    Edtf::from_ical(&ical).unwrap().to_string()
+}
+
+#[test]
+fn ical_output() {
+    let ical = |s: &str| Edtf::parse(s).unwrap().to_ical().map(|v| v.to_string());
+    assert_eq!(
+        ical("2019-12-31").as_deref(),
+        Some("DTSTART;VALUE=DATE:20191231\r\nDTEND;VALUE=DATE:20200101\r\n")
+    );
+    assert_eq!(
+        ical("2019-XX-XX?/2020-02").as_deref(),
+        Some(
+            "DTSTART;VALUE=DATE:20190101\r\nDTEND;VALUE=DATE:20200301\r\n\
+             X-EDTF-DTSTART-CERTAINTY:UNCERTAIN\r\n"
+        )
+    );
+    assert_eq!(
+        ical("2019-08-17T00:30:00+01").as_deref(),
+        Some("DTSTART:20190816T233000Z\r\n")
+    );
+    assert_eq!(ical("2019/.."), None);
+    assert_eq!(ical("/2019"), None);
+    assert_eq!(ical("Y20000"), None);
+    assert_eq!(ical("-0001"), None);
+    assert_eq!(ical("9999-12-31"), None);
+}
+
+#[test]
+fn ical_roundtrip() {
+    assert_eq!(roundtrip("2019"), "2019");
+    assert_eq!(roundtrip("2019-06-XX"), "2019-06");
+    assert_eq!(roundtrip("2019~"), "2019~");
+    assert_eq!(roundtrip("2019-06-07%"), "2019-06-07%");
+    assert_eq!(roundtrip("2019?/2020~"), "2019?/2020~");
+    assert_eq!(roundtrip("2019-01-01/2019-12-31"), "2019");
+    assert_eq!(
+        roundtrip("2019-01-15?/2019-01-15~"),
+        "2019-01-15?/2019-01-15~"
+    );
+    assert_eq!(roundtrip("193X"), "193X");
+    assert_eq!(roundtrip("2019-08-17T23:59:30Z"), "2019-08-17T23:59:30Z");
+    assert_eq!(roundtrip("2019-08-17T23:59:30"), "2019-08-17T23:59:30");
+}
+
+#[test]
+fn ical_parse() {
+    let parse = |s: &str| Edtf::from_ical(s).map(|e| e.to_string());
+    // folded lines, lowercase names, quoted parameters and other properties
+    let ics = "BEGIN:VEVENT\r\nDESCRIPTION:a long\r\n  description: with colons\r\n\
+               dtstart;TZID=\"America/New_York\":20190101T090000\r\n\
+               DTEND;TZID=\"America/New_York\":20190103T090000\r\nEND:VEVENT\r\n";
+    assert_eq!(parse(ics), Ok("2019-01-01/2019-01-03".into()));
+    assert_eq!(
+        parse("DTSTART:20190101T090000Z\nDTEND:20190101T100000Z\n"),
+        Ok("2019-01-01T09:00:00Z".into())
+    );
+    assert_eq!(parse("DTSTART:20190101\n"), Ok("2019-01-01".into()));
+    assert_eq!(parse("SUMMARY:no dates\n"), Err(ParseError::Invalid));
+    assert_eq!(parse("DTSTART:20190230\n"), Err(ParseError::OutOfRange));
+    assert_eq!(
+        parse("DTSTART;VALUE=DATE:20190101T090000\n"),
+        Err(ParseError::Invalid)
+    );
+    assert_eq!(
+        parse("DTSTART;VALUE=DATE:20190102\nDTEND;VALUE=DATE:20190101\n"),
+        Err(ParseError::OutOfRange)
+    );
+    assert_eq!(
+        parse("DTSTART:20200110T100000Z\nDTEND:20200105T100000Z\n"),
+        Err(ParseError::OutOfRange)
+    );
+    assert_eq!(
+        parse("DTSTART:20200110T100000Z\nDTEND:20200110T095959Z\n"),
+        Err(ParseError::OutOfRange)
+    );
+    assert_eq!(
+        parse("DTSTART:20200110T100000Z\nDTEND:20200110T100000Z\n"),
+        Ok("2020-01-10T10:00:00Z".into())
+    );
+    assert_eq!(
+        parse("DTSTART;VALUE=DATE:20190101\nX-EDTF-DTSTART-CERTAINTY:MAYBE\n"),
+        Err(ParseError::Invalid)
+    );
+}
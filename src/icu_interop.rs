@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use core::num::NonZeroU8;
+
+use icu_calendar::{AsCalendar, Date, Iso};
+
+use crate::level_1::Edtf;
+use crate::DateComplete;
+
+impl DateComplete {
+    /// Converts self to an [icu_calendar::Date] in any calendar, e.g. an
+    /// [icu_calendar::AnyCalendar].
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use icu_calendar::{AnyCalendar, AnyCalendarKind};
+    ///
+    /// let hebrew = AnyCalendar::new(AnyCalendarKind::Hebrew);
+    /// let date = DateComplete::from_ymd(2023, 9, 16).to_icu(hebrew);
+    /// assert_eq!(date.year().number, 5784);
+    /// assert_eq!(date.month().ordinal, 1);
+    /// assert_eq!(date.day_of_month().0, 1);
+    /// ```
+    pub fn to_icu<A: AsCalendar>(&self, calendar: A) -> Date<A> {
+        let iso = Date::try_new_iso_date(self.year, self.month.get(), self.day.get())
+            .expect("DateComplete should be a valid ISO date");
+        Date::new_from_iso(iso, calendar)
+    }
+}
+
+/// Converts from an [icu_calendar::Date] in any calendar, via the ISO calendar.
+impl<A: AsCalendar> From<Date<A>> for DateComplete {
+    fn from(date: Date<A>) -> Self {
+        Self::from(&date)
+    }
+}
+
+/// Converts from an [icu_calendar::Date] in any calendar, via the ISO calendar.
+impl<A: AsCalendar> From<&Date<A>> for DateComplete {
+    fn from(date: &Date<A>) -> Self {
+        let iso: Date<Iso> = date.to_iso();
+        Self {
+            year: iso.year().number,
+            month: NonZeroU8::new(iso.month().ordinal as u8).unwrap(),
+            day: NonZeroU8::new(iso.day_of_month().0 as u8).unwrap(),
+        }
+    }
+}
+
+/// # ICU calendars
+impl Edtf {
+    /// The month containing an [icu_calendar::Date], in its own calendar, as the interval of
+    /// Gregorian days it covers. Returns None if the years are out of range for EDTF.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// use edtf::DateComplete;
+    /// use icu_calendar::{AnyCalendar, AnyCalendarKind};
+    ///
+    /// let hebrew = AnyCalendar::new(AnyCalendarKind::Hebrew);
+    /// let date = DateComplete::from_ymd(2023, 9, 20).to_icu(hebrew);
+    /// // Tishri 5784
+    /// let month = Edtf::from_icu_month(&date);
+    /// assert_eq!(month, Some(Edtf::parse("2023-09-16/2023-10-15").unwrap()));
+    /// ```
+    pub fn from_icu_month<A: AsCalendar>(date: &Date<A>) -> Option<Self> {
+        let day = DateComplete::from(date);
+        let first = day.add_days(1 - date.day_of_month().0 as i64)?;
+        let last = first.add_days(date.days_in_month() as i64 - 1)?;
+        Self::from_day_range(first, last)
+    }
+
+    /// The year containing an [icu_calendar::Date], in its own calendar, as the interval of
+    /// Gregorian days it covers. Returns None if the years are out of range for EDTF.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// use edtf::DateComplete;
+    /// use icu_calendar::{AnyCalendar, AnyCalendarKind};
+    ///
+    /// let hijri = AnyCalendar::new(AnyCalendarKind::IslamicCivil);
+    /// let date = DateComplete::from_ymd(2024, 1, 1).to_icu(hijri);
+    /// // 1445 AH
+    /// let year = Edtf::from_icu_year(&date);
+    /// assert_eq!(year, Some(Edtf::parse("2023-07-19/2024-07-07").unwrap()));
+    /// ```
+    pub fn from_icu_year<A: AsCalendar>(date: &Date<A>) -> Option<Self> {
+        let day = DateComplete::from(date);
+        let first = day.add_days(1 - date.day_of_year_info().day_of_year as i64)?;
+        let last = first.add_days(date.days_in_year() as i64 - 1)?;
+        Self::from_day_range(first, last)
+    }
+}
+
+#[test]
+fn icu_roundtrip() {
+    use icu_calendar::{AnyCalendar, AnyCalendarKind};
+    let kinds = [
+        AnyCalendarKind::Gregorian,
+        AnyCalendarKind::Hebrew,
+        AnyCalendarKind::Coptic,
+        AnyCalendarKind::Persian,
+        AnyCalendarKind::Chinese,
+    ];
+    for &kind in kinds.iter() {
+        for &(y, m, d) in [(2024, 2, 29), (1, 1, 1), (-500, 12, 31), (1970, 1, 1)].iter() {
+            let date = DateComplete::from_ymd(y, m, d);
+            let icu = date.to_icu(AnyCalendar::new(kind));
+            assert_eq!(DateComplete::from(&icu), date, "{:?}", kind);
+        }
+    }
+}
+
+#[test]
+fn icu_intervals() {
+    use icu_calendar::{AnyCalendar, AnyCalendarKind};
+    let on = |kind, y, m, d| DateComplete::from_ymd(y, m, d).to_icu(AnyCalendar::new(kind));
+    let parse = |s| Some(Edtf::parse(s).unwrap());
+
+    // Gregorian months and years come out in their compact forms
+    let greg = on(AnyCalendarKind::Gregorian, 2024, 2, 10);
+    assert_eq!(Edtf::from_icu_month(&greg), parse("2024-02"));
+    assert_eq!(Edtf::from_icu_year(&greg), parse("2024"));
+
+    // Adar II 5784
+    let adar = on(AnyCalendarKind::Hebrew, 2024, 3, 24);
+    assert_eq!(Edtf::from_icu_month(&adar), parse("2024-03-11/2024-04-08"));
+    assert_eq!(Edtf::from_icu_year(&adar), parse("2023-09-16/2024-10-02"));
+
+    // Nowruz 1403
+    let persian = on(AnyCalendarKind::Persian, 2024, 3, 20);
+    assert_eq!(
+        Edtf::from_icu_month(&persian),
+        parse("2024-03-20/2024-04-19")
+    );
+}
@@ -0,0 +1,499 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Julian calendar
+//!
+//! EDTF dates are always in the proleptic Gregorian calendar, but many historical documents are
+//! dated in the Julian calendar. [JulianDate] converts single days, and
+//! [Edtf::julian_to_gregorian] converts a whole [level_1::Edtf](Edtf) transcribed from a Julian
+//! source.
+//!
+//! Years here are numbered astronomically like in EDTF, i.e. `0` is 1 BCE. The Julian year is
+//! also taken to start on 1 January. For dates written with a different legal new year, including
+//! dual dates like `19 February 1683/4`, use [OldStyle].
+//!
+//! ```
+//! use edtf::DateComplete;
+//! use edtf::julian::JulianDate;
+//! use edtf::level_1::Edtf;
+//!
+//! // the last day of the Julian calendar in Rome was followed by 1582-10-15
+//! let julian = JulianDate::from_ymd(1582, 10, 4);
+//! assert_eq!(julian.to_gregorian(), Some(DateComplete::from_ymd(1582, 10, 14)));
+//!
+//! // Julian 1700 was a leap year, so it ends a day later
+//! let edtf = Edtf::parse("1700-02").unwrap();
+//! assert_eq!(edtf.julian_to_gregorian(), Some(Edtf::parse("1700-02-11/1700-03-11").unwrap()));
+//! ```
+
+use core::convert::TryInto;
+use core::fmt;
+
+use crate::level_1::{Certainty, Date, Edtf, Precision};
+use crate::{DateComplete, DateTime, ParseError};
+
+/// Days from the Julian 0000-03-01 to 1970-01-01, where the Julian day count below starts.
+const JULIAN_EPOCH_OFFSET: i64 = 719_470;
+
+fn is_julian_leap_year(year: i32) -> bool {
+    year.rem_euclid(4) == 0
+}
+
+fn julian_days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_julian_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days since (Gregorian) 1970-01-01. Same approach as
+/// [crate::common::days_from_civil], with four-year cycles instead of 400.
+fn days_from_julian(year: i64, month: u32, day: u32) -> i64 {
+    let (m, d) = (month as i64, day as i64);
+    let y = if m <= 2 { year - 1 } else { year };
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d - 1;
+    y * 365 + y.div_euclid(4) + doy - JULIAN_EPOCH_OFFSET
+}
+
+/// The inverse of [days_from_julian].
+fn julian_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + JULIAN_EPOCH_OFFSET;
+    let era = z.div_euclid(1461);
+    let doe = z - era * 1461;
+    let yoe = (doe - doe / 1460) / 365;
+    let doy = doe - 365 * yoe;
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 4 + if m <= 2 { 1 } else { 0 };
+    (y, m as u32, d as u32)
+}
+
+/// A day in the proleptic Julian calendar.
+///
+/// The `Ord` implementation is chronological.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JulianDate {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl JulianDate {
+    /// Create a Julian date. Panics if the date is invalid.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
+        Self::from_ymd_opt(year, month, day).expect("invalid julian date")
+    }
+
+    /// Create a Julian date. Returns None if the date does not exist in the Julian calendar,
+    /// e.g. `1900-02-30`. Note that `1900-02-29` does exist.
+    pub fn from_ymd_opt(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day < 1 || day > julian_days_in_month(year, month) {
+            return None;
+        }
+        Some(Self {
+            year,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+
+    /// Gets the year
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+    /// Gets the month
+    pub fn month(&self) -> u32 {
+        self.month as u32
+    }
+    /// Gets the day
+    pub fn day(&self) -> u32 {
+        self.day as u32
+    }
+
+    /// Converts to the same day in the proleptic Gregorian calendar. Returns None if the year
+    /// does not fit in an i32.
+    pub fn to_gregorian(&self) -> Option<DateComplete> {
+        let days = days_from_julian(self.year as i64, self.month(), self.day());
+        DateComplete::from_days_since_epoch(days)
+    }
+
+    /// Converts from a day in the proleptic Gregorian calendar. Returns None if the year does not
+    /// fit in an i32.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::julian::JulianDate;
+    /// let date = DateComplete::from_ymd(1752, 9, 14);
+    /// assert_eq!(JulianDate::from_gregorian(date), Some(JulianDate::from_ymd(1752, 9, 3)));
+    /// ```
+    pub fn from_gregorian(date: DateComplete) -> Option<Self> {
+        let (year, month, day) = julian_from_days(date.days_since_epoch());
+        Self::from_ymd_opt(year.try_into().ok()?, month, day)
+    }
+}
+
+impl fmt::Display for JulianDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = crate::helpers::sign_str_if_neg(self.year);
+        write!(f, "{}{:04}", sign, self.year.abs())?;
+        write!(f, "-{:02}", self.month)?;
+        write!(f, "-{:02}", self.day)?;
+        Ok(())
+    }
+}
+
+/// The first and last Gregorian days of a date read as Julian.
+fn gregorian_days(date: Date) -> Option<(DateComplete, DateComplete)> {
+    let first = date.first_day();
+    let last = date.last_day();
+    // first_day is always valid in both calendars; last_day may be the end of a Gregorian month
+    let last_day = match date.precision() {
+        Precision::Day(..) => last.day(),
+        _ => julian_days_in_month(last.year(), last.month()),
+    };
+    let first = JulianDate::from_ymd_opt(first.year(), first.month(), first.day())?;
+    let last = JulianDate::from_ymd_opt(last.year(), last.month(), last_day)?;
+    Some((first.to_gregorian()?, last.to_gregorian()?))
+}
+
+fn day_with_certainty(day: DateComplete, certainty: Certainty) -> Option<Date> {
+    Date::from_ymd_opt(day.year(), day.month(), day.day()).map(|d| d.and_certainty(certainty))
+}
+
+/// # Julian calendar
+impl Edtf {
+    /// Reads this EDTF as if it had been written in the Julian calendar, and converts it to the
+    /// proleptic Gregorian calendar, which is what EDTF is meant to contain.
+    ///
+    /// A single day converts to a single day. A month, year, season, decade or century does not
+    /// line up with Gregorian months and years, so it widens into an interval of days, e.g. Julian
+    /// `1700` is `1700-01-11/1701-01-11`. Certainty is kept on each end, and times of day are
+    /// unchanged.
+    ///
+    /// Leap days that only exist in the Julian calendar, like `1700-02-29`, cannot be written as
+    /// EDTF at all; use [JulianDate] for those.
+    ///
+    /// Returns None for [Edtf::YYear], and if any year goes out of range.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let edtf = Edtf::parse("1582-10-05?/1582-10").unwrap();
+    /// let greg = Edtf::parse("1582-10-15?/1582-11-10").unwrap();
+    /// assert_eq!(edtf.julian_to_gregorian(), Some(greg));
+    /// ```
+    pub fn julian_to_gregorian(&self) -> Option<Self> {
+        let start = |d: Date| day_with_certainty(gregorian_days(d)?.0, d.certainty());
+        let end = |d: Date| day_with_certainty(gregorian_days(d)?.1, d.certainty());
+        Some(match *self {
+            Edtf::Date(d) => {
+                let (first, last) = gregorian_days(d)?;
+                match Edtf::from_day_range(first, last)? {
+                    Edtf::Date(x) => Edtf::Date(x.and_certainty(d.certainty())),
+                    Edtf::Interval(a, b) => Edtf::Interval(
+                        a.and_certainty(d.certainty()),
+                        b.and_certainty(d.certainty()),
+                    ),
+                    _ => return None,
+                }
+            }
+            Edtf::Interval(a, b) => Edtf::Interval(start(a)?, end(b)?),
+            Edtf::IntervalFrom(a, t) => Edtf::IntervalFrom(start(a)?, t),
+            Edtf::IntervalTo(t, b) => Edtf::IntervalTo(t, end(b)?),
+            Edtf::DateTime(dt) => {
+                let d = dt.date();
+                let date =
+                    JulianDate::from_ymd_opt(d.year(), d.month(), d.day())?.to_gregorian()?;
+                Edtf::DateTime(DateTime {
+                    date,
+                    time: dt.time(),
+                })
+            }
+            Edtf::YYear(_) => return None,
+        })
+    }
+}
+
+/// Reads Old Style dates: Julian dates in a calendar where the legal year began on a day other
+/// than 1 January, often written with a dual year between 1 January and the legal new year.
+///
+/// | Old Style (Lady Day)  | Julian                      | EDTF                    |
+/// | --------------------  | ------                      | ----                    |
+/// | `19 February 1683/4`  | 1684-02-19                  | `1684-02-29`            |
+/// | `Feb 1683/84`         | 1684-02                     | `1684-02-11/1684-03-10` |
+/// | `1683/4`              | 1684-01-01 to 1684-03-24    | `1684-01-11/1684-04-03` |
+/// | `1683`                | 1683-03-25 to 1684-03-24    | `1683-04-04/1684-04-03` |
+/// | `19 February 1683`    | 1684-02-19                  | `1684-02-29`            |
+///
+/// A dual year's second part is only the last one or two digits of the following year, so
+/// `1683/1684` is rejected as an EDTF interval rather than read as a dual date.
+///
+/// ```
+/// use edtf::julian::OldStyle;
+/// use edtf::level_1::Edtf;
+/// let edtf = OldStyle::LADY_DAY.parse("19 Feb 1683/4").unwrap();
+/// assert_eq!(edtf, Edtf::parse("1684-02-29").unwrap());
+/// assert!(OldStyle::LADY_DAY.parse("1683/1684").is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OldStyle {
+    start_month: u8,
+    start_day: u8,
+}
+
+impl Default for OldStyle {
+    fn default() -> Self {
+        Self::LADY_DAY
+    }
+}
+
+impl OldStyle {
+    /// Lady Day, 25 March, the legal new year in England until 1752.
+    pub const LADY_DAY: Self = OldStyle {
+        start_month: 3,
+        start_day: 25,
+    };
+
+    /// A legal year starting on the given day of the (Julian) year. Returns None for days that
+    /// don't exist, and for 29 February.
+    pub fn with_year_start(month: u32, day: u32) -> Option<Self> {
+        // 1 is not a leap year
+        JulianDate::from_ymd_opt(1, month, day)?;
+        Some(OldStyle {
+            start_month: month as u8,
+            start_day: day as u8,
+        })
+    }
+
+    fn starts_on_jan_1(&self) -> bool {
+        (self.start_month, self.start_day) == (1, 1)
+    }
+
+    fn is_before_start(&self, month: u32, day: u32) -> bool {
+        (month, day) < (self.start_month as u32, self.start_day as u32)
+    }
+
+    /// Days since 1970-01-01 of the legal new year in the calendar year `year`.
+    fn start_in(&self, year: i64) -> i64 {
+        days_from_julian(year, self.start_month as u32, self.start_day as u32)
+    }
+
+    /// Parses `[[day] month] year[/y]`, e.g. `1683/4`, `1683/84`, `Feb 1683/4`, or
+    /// `19 February 1683/4`, and converts it to the proleptic Gregorian calendar. Months are
+    /// English names or abbreviations.
+    ///
+    /// Without a dual year, the year is the legal year, so with [OldStyle::LADY_DAY] a bare year
+    /// runs from 25 March to 24 March, and `February 1683` is in 1684. A bare month that
+    /// contains the legal new year is ambiguous, and returns [ParseError::Invalid].
+    pub fn parse(&self, input: &str) -> Result<Edtf, ParseError> {
+        let mut tokens = input.split_whitespace().rev();
+        let year = tokens.next().ok_or(ParseError::Invalid)?;
+        let month = match tokens.next() {
+            Some(m) => Some(crate::common::english_month(m).ok_or(ParseError::Invalid)?),
+            None => None,
+        };
+        let day = match tokens.next() {
+            Some(d) if d.len() <= 2 => Some(d.parse::<u32>().map_err(|_| ParseError::Invalid)?),
+            Some(_) => return Err(ParseError::Invalid),
+            None => None,
+        };
+        if tokens.next().is_some() {
+            return Err(ParseError::Invalid);
+        }
+        let (year, dual) = parse_dual_year(year)?;
+        let (first, last) = match (dual, month, day) {
+            (true, _, _) if self.starts_on_jan_1() => return Err(ParseError::Invalid),
+            // 1 January until the legal new year, in the second year
+            (true, None, _) => (
+                days_from_julian(year + 1, 1, 1),
+                self.start_in(year + 1) - 1,
+            ),
+            (true, Some(m), None) if self.is_before_start(m, 1) => {
+                let first = days_from_julian(year + 1, m, 1);
+                let end_of_month = days_from_julian(year + 1, m, 1)
+                    + julian_days_in_month((year + 1) as i32, m) as i64
+                    - 1;
+                (first, end_of_month.min(self.start_in(year + 1) - 1))
+            }
+            (true, Some(m), Some(d)) if self.is_before_start(m, d) => {
+                let day = julian_day(year + 1, m, d)?;
+                (day, day)
+            }
+            (true, _, _) => return Err(ParseError::Invalid),
+            (false, None, _) => (self.start_in(year), self.start_in(year + 1) - 1),
+            (false, Some(m), None) => {
+                let y = if !self.is_before_start(m, 1) {
+                    year
+                } else if m < self.start_month as u32 {
+                    year + 1
+                } else {
+                    return Err(ParseError::Invalid);
+                };
+                let first = days_from_julian(y, m, 1);
+                (first, first + julian_days_in_month(y as i32, m) as i64 - 1)
+            }
+            (false, Some(m), Some(d)) => {
+                let y = if self.is_before_start(m, d) {
+                    year + 1
+                } else {
+                    year
+                };
+                let day = julian_day(y, m, d)?;
+                (day, day)
+            }
+        };
+        let first = DateComplete::from_days_since_epoch(first).ok_or(ParseError::OutOfRange)?;
+        let last = DateComplete::from_days_since_epoch(last).ok_or(ParseError::OutOfRange)?;
+        Edtf::from_day_range(first, last).ok_or(ParseError::OutOfRange)
+    }
+}
+
+fn julian_day(year: i64, month: u32, day: u32) -> Result<i64, ParseError> {
+    let year = year.try_into().map_err(|_| ParseError::OutOfRange)?;
+    JulianDate::from_ymd_opt(year, month, day).ok_or(ParseError::OutOfRange)?;
+    Ok(days_from_julian(year as i64, month, day))
+}
+
+/// `1683` or a dual year `1683/4`, `1683/84`. The bool is whether it was dual.
+fn parse_dual_year(s: &str) -> Result<(i64, bool), ParseError> {
+    let digits = |s: &str| {
+        if !s.is_empty() && s.len() <= 4 && s.bytes().all(|b| b.is_ascii_digit()) {
+            s.parse::<i64>().map_err(|_| ParseError::Invalid)
+        } else {
+            Err(ParseError::Invalid)
+        }
+    };
+    let (first, second) = match s.find('/') {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let year = digits(first)?;
+    match second {
+        None => Ok((year, false)),
+        // `1683/1684` is an interval, not a dual year
+        Some(second) if second.len() <= 2 => {
+            let modulus = 10i64.pow(second.len() as u32);
+            if digits(second)? == (year + 1) % modulus {
+                Ok((year, true))
+            } else {
+                Err(ParseError::Invalid)
+            }
+        }
+        Some(_) => Err(ParseError::Invalid),
+    }
+}
+
+#[test]
+fn julian_days() {
+    let roundtrip = |y, m, d| {
+        let days = days_from_julian(y, m, d);
+        assert_eq!(julian_from_days(days), (y, m, d));
+        days
+    };
+    assert_eq!(roundtrip(1969, 12, 19), 0);
+    assert_eq!(roundtrip(1, 1, 3), -719_162);
+    roundtrip(0, 2, 29);
+    roundtrip(-1, 12, 31);
+    roundtrip(-4, 2, 29);
+    roundtrip(-4713, 1, 1);
+    roundtrip(1900, 2, 29);
+}
+
+#[test]
+fn julian_to_from_gregorian() {
+    let greg = DateComplete::from_ymd;
+    let jul = JulianDate::from_ymd;
+    let pairs = [
+        (jul(1582, 10, 5), greg(1582, 10, 15)),
+        (jul(1752, 9, 2), greg(1752, 9, 13)),
+        (jul(1700, 2, 29), greg(1700, 3, 11)),
+        (jul(1900, 2, 29), greg(1900, 3, 13)),
+        (jul(1918, 1, 31), greg(1918, 2, 13)),
+        (jul(200, 3, 1), greg(200, 3, 1)),
+        (jul(1, 1, 3), greg(1, 1, 1)),
+        (jul(-4713, 1, 1), greg(-4714, 11, 24)),
+    ];
+    for &(j, g) in pairs.iter() {
+        assert_eq!(j.to_gregorian(), Some(g));
+        assert_eq!(JulianDate::from_gregorian(g), Some(j));
+    }
+    assert!(JulianDate::from_ymd_opt(1900, 2, 29).is_some());
+    assert_eq!(JulianDate::from_ymd_opt(1900, 2, 30), None);
+    assert_eq!(JulianDate::from_ymd_opt(1900, 13, 1), None);
+    assert_eq!(JulianDate::from_ymd_opt(1900, 1, 0), None);
+    assert_eq!(jul(-44, 3, 15).to_string(), "-0044-03-15");
+}
+
+#[test]
+fn julian_edtf() {
+    let conv = |s: &str| {
+        Edtf::parse(s)
+            .unwrap()
+            .julian_to_gregorian()
+            .map(|e| e.to_string())
+    };
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(conv("1582-10-05"), some("1582-10-15"));
+    assert_eq!(conv("1582-10-05~"), some("1582-10-15~"));
+    assert_eq!(conv("1700"), some("1700-01-11/1701-01-11"));
+    assert_eq!(conv("1700-02"), some("1700-02-11/1700-03-11"));
+    assert_eq!(conv("1700-02-XX"), some("1700-02-11/1700-03-11"));
+    assert_eq!(conv("1700-02-28"), some("1700-03-10"));
+    assert_eq!(conv("170X?"), some("1700-01-11?/1710-01-11?"));
+    assert_eq!(conv("1700-21"), some("1700-03-12/1700-06-11"));
+    assert_eq!(conv("0200-03"), some("0200-03"));
+    assert_eq!(conv("0200"), some("0199-12-31/0200-12-31"));
+    assert_eq!(conv("1700/1710?"), some("1700-01-11/1711-01-11?"));
+    assert_eq!(conv("1582-10-05/.."), some("1582-10-15/.."));
+    assert_eq!(conv("/1582-10-04"), some("/1582-10-14"));
+    assert_eq!(conv("1582-10-04T12:00:00Z"), some("1582-10-14T12:00:00Z"));
+    assert_eq!(conv("Y170000002"), None);
+}
+
+#[test]
+fn old_style() {
+    let os = |s: &str| OldStyle::LADY_DAY.parse(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(os("19 February 1683/4"), ok("1684-02-29"));
+    assert_eq!(os("19 Feb 1683/84"), ok("1684-02-29"));
+    assert_eq!(os("24 March 1683/4"), ok("1684-04-03"));
+    assert_eq!(os("Feb 1683/4"), ok("1684-02-11/1684-03-10"));
+    assert_eq!(os("March 1683/4"), ok("1684-03-11/1684-04-03"));
+    assert_eq!(os("1683/4"), ok("1684-01-11/1684-04-03"));
+    assert_eq!(os("1699/700"), Err(ParseError::Invalid));
+    assert_eq!(os("1699/00"), ok("1700-01-11/1700-04-04"));
+    assert_eq!(os("1683"), ok("1683-04-04/1684-04-03"));
+    assert_eq!(os("19 February 1683"), ok("1684-02-29"));
+    assert_eq!(os("25 March 1683"), ok("1683-04-04"));
+    assert_eq!(os("Feb 1683"), ok("1684-02-11/1684-03-10"));
+    assert_eq!(os("April 1683"), ok("1683-04-11/1683-05-10"));
+    // no
+    assert_eq!(os("March 1683"), Err(ParseError::Invalid));
+    assert_eq!(os("25 March 1683/4"), Err(ParseError::Invalid));
+    assert_eq!(os("April 1683/4"), Err(ParseError::Invalid));
+    assert_eq!(os("1683/1684"), Err(ParseError::Invalid));
+    assert_eq!(os("1683/5"), Err(ParseError::Invalid));
+    assert_eq!(os("30 Feb 1683/4"), Err(ParseError::OutOfRange));
+    assert_eq!(os("19 Fbr 1683/4"), Err(ParseError::Invalid));
+    assert_eq!(os(""), Err(ParseError::Invalid));
+
+    let jan = OldStyle::with_year_start(1, 1).unwrap();
+    assert_eq!(jan.parse("1683"), Edtf::parse("1683-01-11/1684-01-10"));
+    assert_eq!(jan.parse("1683/4"), Err(ParseError::Invalid));
+
+    // a legal year starting on the 1st of a month never splits that month
+    let march = OldStyle::with_year_start(3, 1).unwrap();
+    let os = |s: &str| march.parse(s).map(|e| e.to_string());
+    assert_eq!(os("March 1683"), ok("1683-03-11/1683-04-10"));
+    assert_eq!(os("1 March 1683"), ok("1683-03-11"));
+    assert_eq!(os("Feb 1683"), ok("1684-02-11/1684-03-10"));
+    assert_eq!(os("Feb 1683/4"), ok("1684-02-11/1684-03-10"));
+    assert_eq!(os("March 1683/4"), Err(ParseError::Invalid));
+    assert_eq!(OldStyle::with_year_start(2, 29), None);
+    assert_eq!(OldStyle::default(), OldStyle::LADY_DAY);
+}
@@ -0,0 +1,543 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::*;
+use crate::common::days_in_month;
+
+/// One of the thirteen relations in Allen's interval algebra, describing how one [Edtf] (`a`)
+/// lies relative to another (`b`) on the timeline. Each EDTF is taken to cover whole days, so
+/// `2019` meets `2020`, and `2019-12-31` meets `2020-01-01`.
+///
+/// Every pair of intervals is in exactly one of these relations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AllenRelation {
+    /// `a` ends before `b` starts, with a gap of at least a day.
+    Before,
+    /// `a` ends the day before `b` starts.
+    Meets,
+    /// `a` starts first, and ends while `b` is under way.
+    Overlaps,
+    /// They start together, and `a` ends first.
+    Starts,
+    /// `a` starts after and ends before `b`.
+    During,
+    /// `a` starts after `b`, and they end together.
+    Finishes,
+    /// They start and end together.
+    Equals,
+    /// `b` starts after `a`, and they end together. The inverse of [AllenRelation::Finishes].
+    FinishedBy,
+    /// `b` starts after and ends before `a`. The inverse of [AllenRelation::During].
+    Contains,
+    /// They start together, and `b` ends first. The inverse of [AllenRelation::Starts].
+    StartedBy,
+    /// `b` starts first, and ends while `a` is under way. The inverse of
+    /// [AllenRelation::Overlaps].
+    OverlappedBy,
+    /// `b` ends the day before `a` starts. The inverse of [AllenRelation::Meets].
+    MetBy,
+    /// `b` ends before `a` starts, with a gap of at least a day. The inverse of
+    /// [AllenRelation::Before].
+    After,
+}
+
+/// Endpoints, numbered for [Constraint]s: `a`'s start and end, then `b`'s.
+const A_START: usize = 0;
+const A_END: usize = 1;
+const B_START: usize = 2;
+const B_END: usize = 3;
+
+/// An order between two endpoints.
+#[derive(Debug, Copy, Clone)]
+enum Constraint {
+    Less(usize, usize),
+    Equal(usize, usize),
+}
+
+impl Constraint {
+    fn holds(self, points: &[i64; 4]) -> bool {
+        match self {
+            Constraint::Less(x, y) => points[x] < points[y],
+            Constraint::Equal(x, y) => points[x] == points[y],
+        }
+    }
+
+    /// The same constraint with `a` and `b` swapped
+    fn swapped(self) -> Self {
+        let swap = |x: usize| x ^ 2;
+        match self {
+            Constraint::Less(x, y) => Constraint::Less(swap(x), swap(y)),
+            Constraint::Equal(x, y) => Constraint::Equal(swap(x), swap(y)),
+        }
+    }
+}
+
+impl AllenRelation {
+    /// All thirteen relations
+    pub const ALL: [AllenRelation; 13] = [
+        AllenRelation::Before,
+        AllenRelation::Meets,
+        AllenRelation::Overlaps,
+        AllenRelation::Starts,
+        AllenRelation::During,
+        AllenRelation::Finishes,
+        AllenRelation::Equals,
+        AllenRelation::FinishedBy,
+        AllenRelation::Contains,
+        AllenRelation::StartedBy,
+        AllenRelation::OverlappedBy,
+        AllenRelation::MetBy,
+        AllenRelation::After,
+    ];
+
+    /// The relation `b` has to `a`, when `a` has this relation to `b`.
+    ///
+    /// ```
+    /// use edtf::level_1::AllenRelation;
+    /// assert_eq!(AllenRelation::Before.inverse(), AllenRelation::After);
+    /// assert_eq!(AllenRelation::Equals.inverse(), AllenRelation::Equals);
+    /// ```
+    pub fn inverse(self) -> Self {
+        use AllenRelation::*;
+        match self {
+            Before => After,
+            Meets => MetBy,
+            Overlaps => OverlappedBy,
+            Starts => StartedBy,
+            During => Contains,
+            Finishes => FinishedBy,
+            Equals => Equals,
+            FinishedBy => Finishes,
+            Contains => During,
+            StartedBy => Starts,
+            OverlappedBy => Overlaps,
+            MetBy => Meets,
+            After => Before,
+        }
+    }
+
+    /// The order this relation puts the endpoints in, with ends exclusive.
+    fn constraints(self) -> Vec<Constraint> {
+        use Constraint::*;
+        match self {
+            AllenRelation::Before => vec![Less(A_END, B_START)],
+            AllenRelation::Meets => vec![Equal(A_END, B_START)],
+            AllenRelation::Overlaps => vec![
+                Less(A_START, B_START),
+                Less(B_START, A_END),
+                Less(A_END, B_END),
+            ],
+            AllenRelation::Starts => vec![Equal(A_START, B_START), Less(A_END, B_END)],
+            AllenRelation::During => vec![Less(B_START, A_START), Less(A_END, B_END)],
+            AllenRelation::Finishes => vec![Less(B_START, A_START), Equal(A_END, B_END)],
+            AllenRelation::Equals => vec![Equal(A_START, B_START), Equal(A_END, B_END)],
+            inverse => inverse
+                .inverse()
+                .constraints()
+                .into_iter()
+                .map(Constraint::swapped)
+                .collect(),
+        }
+    }
+}
+
+/// Further than any day a [DateComplete] can represent, in days since the epoch.
+const INFINITY: i64 = 1 << 50;
+
+fn bound_days(bound: DayBound) -> i64 {
+    match bound {
+        DayBound::NegativeInfinity => -INFINITY,
+        DayBound::Day(d) => d.days_since_epoch(),
+        DayBound::Infinity => INFINITY,
+    }
+}
+
+/// What is known about when an EDTF starts and ends, in days since the epoch. Ends are
+/// exclusive, i.e. the day after the last day.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(super) struct Extent {
+    pub(super) start: (i64, i64),
+    pub(super) end: (i64, i64),
+    length: (i64, i64),
+}
+
+/// Any length at all, for intervals
+const ANY_LENGTH: (i64, i64) = (1, 4 * INFINITY);
+
+impl Extent {
+    fn exact(first: DayBound, last: DayBound) -> Self {
+        let (start, end) = (bound_days(first), bound_days(last) + 1);
+        Extent {
+            start: (start, start),
+            end: (end, end),
+            length: (end - start, end - start),
+        }
+    }
+
+    /// A span of days, from `first` to `last`, shifted by up to a tolerance.
+    fn shifted(first: DateComplete, last: DateComplete, tolerance: Tolerance) -> Self {
+        let earlier = |d: DateComplete| match tolerance.shift(d, false) {
+            Some(d) => d.days_since_epoch(),
+            None => -INFINITY,
+        };
+        let later = |d: DateComplete| match tolerance.shift(d, true) {
+            Some(d) => d.days_since_epoch(),
+            None => INFINITY,
+        };
+        let start = (earlier(first), later(first));
+        let end = (earlier(last) + 1, later(last) + 1);
+        let days = first.days_between(&last) + 1;
+        // shifting by months or years can change the length a little
+        let lengths = [days, end.0 - start.0, end.1 - start.1];
+        let length = (
+            lengths.iter().copied().min().unwrap_or(days).max(1),
+            lengths.iter().copied().max().unwrap_or(days),
+        );
+        Extent { start, end, length }
+    }
+
+    /// The spans of days a date could be referring to. Masked digits mean one unknown year,
+    /// month or day, e.g. `201X` is one of the years in the 2010s, and approximate dates can be
+    /// shifted by the policy's tolerance.
+    pub(super) fn of_date(date: &Date, policy: &InterpretationPolicy) -> Vec<Self> {
+        let tolerance = policy.tolerance_for(date);
+        let (first, last) = (date.first_day(), date.last_day());
+        let ymd = DateComplete::from_ymd;
+        let units: Vec<(DateComplete, DateComplete)> = match date.precision() {
+            Precision::Century(_) | Precision::Decade(_) => (first.year()..=last.year())
+                .map(|y| (ymd(y, 1, 1), ymd(y, 12, 31)))
+                .collect(),
+            Precision::MonthOfYear(y) => (1..=12)
+                .map(|m| (ymd(y, m, 1), ymd(y, m, days_in_month(y, m as u8) as u32)))
+                .collect(),
+            Precision::DayOfYear(_) | Precision::DayOfMonth(..) => (0..=first.days_between(&last))
+                .filter_map(|n| first.add_days(n))
+                .map(|d| (d, d))
+                .collect(),
+            Precision::Year(_)
+            | Precision::Season(..)
+            | Precision::Month(..)
+            | Precision::Day(..) => {
+                vec![(first, last)]
+            }
+        };
+        units
+            .into_iter()
+            .map(|(f, l)| Extent::shifted(f, l, tolerance))
+            .collect()
+    }
+
+    /// The smallest extent covering all of `extents`, as one end of an interval.
+    pub(super) fn hull(extents: &[Extent]) -> Self {
+        let min = |f: fn(&Extent) -> i64| extents.iter().map(f).min().unwrap_or(-INFINITY);
+        let max = |f: fn(&Extent) -> i64| extents.iter().map(f).max().unwrap_or(INFINITY);
+        Extent {
+            start: (min(|e| e.start.0), max(|e| e.start.1)),
+            end: (min(|e| e.end.0), max(|e| e.end.1)),
+            length: ANY_LENGTH,
+        }
+    }
+
+    /// The spans of days an EDTF could be referring to. The masked dates at the ends of an
+    /// interval could be any day in their range, not only the first or last of a unit.
+    fn of_edtf(edtf: &Edtf, policy: &InterpretationPolicy) -> Vec<Self> {
+        let unknown = (-INFINITY, INFINITY + 1);
+        let start = |d: &Date| Extent::hull(&Extent::of_date(d, policy)).start;
+        let end = |d: &Date| Extent::hull(&Extent::of_date(d, policy)).end;
+        let interval = |start, end| {
+            vec![Extent {
+                start,
+                end,
+                length: ANY_LENGTH,
+            }]
+        };
+        match edtf {
+            Edtf::Date(d) => Extent::of_date(d, policy),
+            Edtf::Interval(a, b) => interval(start(a), end(b)),
+            Edtf::IntervalFrom(a, Terminal::Open) => {
+                interval(start(a), (INFINITY + 1, INFINITY + 1))
+            }
+            Edtf::IntervalFrom(a, Terminal::Unknown) => interval(start(a), unknown),
+            Edtf::IntervalTo(Terminal::Open, b) => interval((-INFINITY, -INFINITY), end(b)),
+            Edtf::IntervalTo(Terminal::Unknown, b) => interval(unknown, end(b)),
+            Edtf::DateTime(_) | Edtf::YYear(_) => {
+                let (first, last) = edtf.bounds();
+                vec![Extent::exact(first, last)]
+            }
+        }
+    }
+}
+
+/// Whether the endpoints of `a` and `b` can be chosen within their extents to satisfy all of
+/// `constraints`. This is a system of difference constraints, so it is feasible exactly when
+/// its constraint graph has no negative cycle.
+fn feasible(a: &Extent, b: &Extent, constraints: &[Constraint]) -> bool {
+    // node 4 is zero, so `lo <= x` is `0 - x <= -lo`
+    const ZERO: usize = 4;
+    // (i, j, w) means x[j] - x[i] <= w
+    let mut edges: Vec<(usize, usize, i64)> = Vec::with_capacity(20);
+    for (ext, start, end) in [(a, A_START, A_END), (b, B_START, B_END)].iter() {
+        edges.push((*start, ZERO, -ext.start.0));
+        edges.push((ZERO, *start, ext.start.1));
+        edges.push((*end, ZERO, -ext.end.0));
+        edges.push((ZERO, *end, ext.end.1));
+        edges.push((*end, *start, -ext.length.0));
+        edges.push((*start, *end, ext.length.1));
+    }
+    for c in constraints {
+        match *c {
+            Constraint::Less(x, y) => edges.push((y, x, -1)),
+            Constraint::Equal(x, y) => {
+                edges.push((y, x, 0));
+                edges.push((x, y, 0));
+            }
+        }
+    }
+    let mut dist = [0i64; 5];
+    for _ in 0..dist.len() {
+        let mut changed = false;
+        for &(i, j, w) in &edges {
+            if dist[i] + w < dist[j] {
+                dist[j] = dist[i] + w;
+                changed = true;
+            }
+        }
+        if !changed {
+            return true;
+        }
+    }
+    false
+}
+
+/// # Interval relations
+impl Edtf {
+    /// The [AllenRelation] between self and `other`, taking both literally as the spans from
+    /// their earliest to latest days, as given by [Edtf::bounds]. Open and unknown ends extend
+    /// to infinity, so `2019/..` and `2020/..` finish together.
+    ///
+    /// ```
+    /// use edtf::level_1::{AllenRelation, Edtf};
+    /// let a = Edtf::parse("2019-06").unwrap();
+    /// let b = Edtf::parse("2019").unwrap();
+    /// assert_eq!(a.relation(&b), AllenRelation::During);
+    /// assert_eq!(b.relation(&a), AllenRelation::Contains);
+    /// let c = Edtf::parse("2020/2021").unwrap();
+    /// assert_eq!(b.relation(&c), AllenRelation::Meets);
+    /// ```
+    pub fn relation(&self, other: &Edtf) -> AllenRelation {
+        let (a_first, a_last) = self.bounds();
+        let (b_first, b_last) = other.bounds();
+        let points = [
+            bound_days(a_first),
+            bound_days(a_last) + 1,
+            bound_days(b_first),
+            bound_days(b_last) + 1,
+        ];
+        AllenRelation::ALL
+            .iter()
+            .copied()
+            .find(|r| r.constraints().into_iter().all(|c| c.holds(&points)))
+            .expect("the Allen relations are exhaustive")
+    }
+
+    /// Every [AllenRelation] self and `other` could be in, under the default
+    /// [InterpretationPolicy]. See [InterpretationPolicy::relation_possible].
+    ///
+    /// ```
+    /// use edtf::level_1::{AllenRelation, Edtf};
+    /// let a = Edtf::parse("201X").unwrap();
+    /// let b = Edtf::parse("2015").unwrap();
+    /// use AllenRelation::*;
+    /// assert_eq!(a.relation_possible(&b), vec![Before, Meets, Equals, MetBy, After]);
+    /// assert_eq!(a.relation_certain(&b), None);
+    /// ```
+    pub fn relation_possible(&self, other: &Edtf) -> Vec<AllenRelation> {
+        InterpretationPolicy::default().relation_possible(self, other)
+    }
+
+    /// The [AllenRelation] self and `other` are certainly in, under the default
+    /// [InterpretationPolicy]. See [InterpretationPolicy::relation_certain].
+    pub fn relation_certain(&self, other: &Edtf) -> Option<AllenRelation> {
+        InterpretationPolicy::default().relation_certain(self, other)
+    }
+}
+
+/// # Interval relations
+impl InterpretationPolicy {
+    /// Every [AllenRelation] `a` and `b` could be in, in the order of [AllenRelation::ALL].
+    ///
+    /// Unlike [Edtf::relation], this treats masked digits as one unknown year, month or day, so
+    /// `201X` is one year in the 2010s; approximate dates as shifted by up to this policy's
+    /// tolerance; and unknown ends of intervals as anywhere after the start or before the end.
+    /// A masked date at the end of an interval could start or end on any day in its range, so
+    /// `2019-XX/2020` starts some time between 1 January and 1 December 2019.
+    pub fn relation_possible(&self, a: &Edtf, b: &Edtf) -> Vec<AllenRelation> {
+        let constraints: Vec<_> = AllenRelation::ALL.iter().map(|r| r.constraints()).collect();
+        let mut possible = [false; 13];
+        let b = Extent::of_edtf(b, self);
+        'pairs: for a in Extent::of_edtf(a, self) {
+            for b in &b {
+                // the common case when there are many candidates
+                if a.end.1 < b.start.0 {
+                    possible[0] = true;
+                    continue;
+                } else if b.end.1 < a.start.0 {
+                    possible[12] = true;
+                    continue;
+                }
+                for (i, constraints) in constraints.iter().enumerate() {
+                    if !possible[i] && feasible(&a, b, constraints) {
+                        possible[i] = true;
+                    }
+                }
+                if possible.iter().all(|&p| p) {
+                    break 'pairs;
+                }
+            }
+        }
+        AllenRelation::ALL
+            .iter()
+            .zip(possible.iter())
+            .filter(|(_, &p)| p)
+            .map(|(&r, _)| r)
+            .collect()
+    }
+
+    /// The [AllenRelation] `a` and `b` are in however their masks, approximations and unknown
+    /// ends are interpreted, or None if more than one is possible. See
+    /// [InterpretationPolicy::relation_possible].
+    ///
+    /// ```
+    /// use edtf::level_1::{AllenRelation, Edtf, InterpretationPolicy};
+    /// let a = Edtf::parse("1950~").unwrap();
+    /// let b = Edtf::parse("1955").unwrap();
+    /// let policy = InterpretationPolicy::default();
+    /// assert_eq!(policy.relation_certain(&a, &b), None);
+    /// let policy = policy.with_year(edtf::level_1::Tolerance::Years(2));
+    /// assert_eq!(policy.relation_certain(&a, &b), Some(AllenRelation::Before));
+    /// ```
+    pub fn relation_certain(&self, a: &Edtf, b: &Edtf) -> Option<AllenRelation> {
+        match self.relation_possible(a, b).as_slice() {
+            [only] => Some(*only),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+fn relation(a: &str, b: &str) -> AllenRelation {
+    Edtf::parse(a).unwrap().relation(&Edtf::parse(b).unwrap())
+}
+
+#[cfg(test)]
+fn possible(a: &str, b: &str) -> Vec<AllenRelation> {
+    Edtf::parse(a)
+        .unwrap()
+        .relation_possible(&Edtf::parse(b).unwrap())
+}
+
+#[test]
+fn allen_strict() {
+    use AllenRelation::*;
+    assert_eq!(relation("2018", "2020"), Before);
+    assert_eq!(relation("2019", "2020"), Meets);
+    assert_eq!(relation("2019-12-31", "2020-01-01"), Meets);
+    assert_eq!(relation("2019/2020", "2020/2021"), Overlaps);
+    assert_eq!(relation("2019-01", "2019"), Starts);
+    assert_eq!(relation("2019-06", "2019"), During);
+    assert_eq!(relation("2019-12", "2019"), Finishes);
+    assert_eq!(relation("2019", "2019-01-01/2019-12-31"), Equals);
+    assert_eq!(relation("2019", "2019-12"), FinishedBy);
+    assert_eq!(relation("201X", "2015"), Contains);
+    assert_eq!(relation("2019", "2019-01"), StartedBy);
+    assert_eq!(relation("2020/2021", "2019/2020"), OverlappedBy);
+    assert_eq!(relation("2020", "2019"), MetBy);
+    assert_eq!(relation("2021", "2019"), After);
+    assert_eq!(relation("../2019", "2000"), Contains);
+    assert_eq!(relation("2019/..", "2020/"), FinishedBy);
+    assert_eq!(relation("2019-24", "2020-02"), FinishedBy);
+    assert_eq!(relation("2019-07-15T23:00:00Z", "2019-07"), During);
+    assert_eq!(relation("Y-170000", "Y170000"), Before);
+    for a in ["2019", "2019-06/2020", "201X", "/2019"].iter() {
+        for b in ["2019-06", "2020/..", "2019-24"].iter() {
+            assert_eq!(relation(a, b), relation(b, a).inverse());
+        }
+    }
+}
+
+#[test]
+fn allen_possible() {
+    use AllenRelation::*;
+    // nothing uncertain: only the strict relation is possible
+    assert_eq!(possible("2019-06", "2019"), vec![During]);
+    assert_eq!(possible("2019", "2020"), vec![Meets]);
+    assert_eq!(possible("2019/2020", "2019-06/2020-06"), vec![Contains]);
+    // 2019-XX is one month of 2019
+    assert_eq!(
+        possible("2019-XX", "2019-06"),
+        vec![Before, Meets, Equals, MetBy, After]
+    );
+    assert_eq!(possible("2019-XX", "2019"), vec![Starts, During, Finishes]);
+    assert_eq!(
+        possible("19XX", "1950-24"),
+        vec![Before, Overlaps, OverlappedBy, After]
+    );
+    // the interval starts in one of the months of 2019
+    assert_eq!(
+        possible("2019-XX/2020", "2019-06/2020-06"),
+        vec![Contains, StartedBy, OverlappedBy]
+    );
+    // an unknown end could be anywhere after the start
+    assert_eq!(
+        possible("2019/", "2021"),
+        vec![Before, Meets, Overlaps, FinishedBy, Contains]
+    );
+    assert_eq!(possible("2019/..", "2021"), vec![Contains]);
+    // approximate by +/- 5 years by default
+    assert_eq!(possible("1950~", "1956"), vec![Before, Meets]);
+    assert_eq!(possible("1950~", "1957"), vec![Before]);
+    // uncertain dates are literal by default
+    assert_eq!(possible("1950?", "1951"), vec![Meets]);
+    let policy = InterpretationPolicy::default().with_uncertain_as_approximate(true);
+    let a = Edtf::parse("1950?").unwrap();
+    let b = Edtf::parse("1951").unwrap();
+    assert_eq!(
+        policy.relation_possible(&a, &b),
+        vec![Before, Meets, Overlaps, Equals, OverlappedBy, MetBy, After]
+    );
+    assert_eq!(policy.relation_certain(&a, &b), None);
+    assert_eq!(a.relation_certain(&b), Some(Meets));
+    for a in [
+        "2019-XX",
+        "2019/",
+        "1950~",
+        "19XX",
+        "2019-06-XX",
+        "2019-XX-XX~",
+    ]
+    .iter()
+    {
+        for b in ["2019-06", "2020/..", "1950-24", "/2019", "2019-XX-XX"].iter() {
+            let forward = possible(a, b);
+            let mut backward: Vec<_> = possible(b, a).into_iter().map(|r| r.inverse()).collect();
+            backward.sort();
+            assert_eq!(forward, backward, "{} vs {}", a, b);
+            assert!(!forward.is_empty(), "{} vs {}", a, b);
+        }
+    }
+}
+
+#[test]
+fn allen_extremes() {
+    use AllenRelation::*;
+    let year = i32::MAX >> 4;
+    let last = Date::from_ymd(year, 12, 0).and_certainty(Certainty::Approximate);
+    let last = Edtf::Date(last);
+    let open = Edtf::parse("2019/..").unwrap();
+    assert_eq!(last.relation(&open), During);
+    // widening runs off the end of the calendar, so it could end whenever 2019/.. does
+    assert_eq!(last.relation_possible(&open), vec![During, Finishes]);
+}
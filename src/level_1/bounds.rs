@@ -0,0 +1,521 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::iter::StepSize;
+use super::*;
+use crate::common::{days_from_civil, days_in_month};
+
+impl Season {
+    /// The first and last months of a season, as `(year offset, month)` pairs.
+    ///
+    /// EDTF does not say which hemisphere a season is in. We use the northern hemisphere's
+    /// meteorological seasons, where winter runs from December into February of the following
+    /// year.
+    pub(crate) fn month_span(self) -> ((i32, u32), (i32, u32)) {
+        match self {
+            Season::Spring => ((0, 3), (0, 5)),
+            Season::Summer => ((0, 6), (0, 8)),
+            Season::Autumn => ((0, 9), (0, 11)),
+            Season::Winter => ((0, 12), (1, 2)),
+        }
+    }
+}
+
+/// The years covered by a `201X`- or `20XX`-style year, where `y` is the stored year and `n` is
+/// 10 or 100. Negative years count the other way, i.e. `-001X` is `-0019..=-0010`.
+fn masked_years(y: i32, n: i32) -> (i32, i32) {
+    if y < 0 {
+        (y - (n - 1), y)
+    } else {
+        (y, y + (n - 1))
+    }
+}
+
+fn last_of_month(year: i32, month: u32) -> DateComplete {
+    DateComplete::from_ymd(year, month, days_in_month(year, month as u8) as u32)
+}
+
+/// The year and month of a date whose unspecified month [Date::unspec_start] or
+/// [Date::unspec_end] filled in.
+fn filled_month(date: Option<Date>) -> (i32, u32) {
+    match date.map(|d| d.precision()) {
+        Some(Precision::Month(y, m)) => (y, m),
+        _ => unreachable!("reify_unspecified fills in the month"),
+    }
+}
+
+/// The day of a date whose unspecified month and day [Date::unspec_start] or
+/// [Date::unspec_end] filled in.
+fn filled_day(date: Option<Date>) -> DateComplete {
+    date.and_then(|d| d.complete())
+        .expect("reify_unspecified fills in the month and day")
+}
+
+impl Date {
+    /// The earliest day this date could be referring to. Unspecified months and days are filled
+    /// in the same way as when iterating.
+    pub(crate) fn first_day(&self) -> DateComplete {
+        match self.precision() {
+            Precision::Century(y) => DateComplete::from_ymd(masked_years(y, 100).0, 1, 1),
+            Precision::Decade(y) => DateComplete::from_ymd(masked_years(y, 10).0, 1, 1),
+            Precision::Year(y) => DateComplete::from_ymd(y, 1, 1),
+            Precision::Season(y, s) => {
+                let ((dy, m), _) = s.month_span();
+                DateComplete::from_ymd(y + dy, m, 1)
+            }
+            Precision::MonthOfYear(_) | Precision::Month(..) => {
+                let (y, m) = filled_month(self.unspec_start(StepSize::Month));
+                DateComplete::from_ymd(y, m, 1)
+            }
+            Precision::DayOfYear(_) | Precision::DayOfMonth(..) | Precision::Day(..) => {
+                filled_day(self.unspec_start(StepSize::Day))
+            }
+        }
+    }
+
+    /// The latest day this date could be referring to.
+    pub(crate) fn last_day(&self) -> DateComplete {
+        match self.precision() {
+            Precision::Century(y) => DateComplete::from_ymd(masked_years(y, 100).1, 12, 31),
+            Precision::Decade(y) => DateComplete::from_ymd(masked_years(y, 10).1, 12, 31),
+            Precision::Year(y) => DateComplete::from_ymd(y, 12, 31),
+            Precision::Season(y, s) => {
+                let (_, (dy, m)) = s.month_span();
+                last_of_month(y + dy, m)
+            }
+            Precision::MonthOfYear(_) | Precision::Month(..) => {
+                let (y, m) = filled_month(self.unspec_end(StepSize::Month));
+                last_of_month(y, m)
+            }
+            Precision::DayOfYear(_) | Precision::DayOfMonth(..) | Precision::Day(..) => {
+                filled_day(self.unspec_end(StepSize::Day))
+            }
+        }
+    }
+}
+
+/// The units [Edtf::from_day_range] will try to express a range of days in, coarsest first.
+const COMPACT_UNITS: [StepSize; 5] = [
+    StepSize::Century,
+    StepSize::Decade,
+    StepSize::Year,
+    StepSize::Month,
+    StepSize::Day,
+];
+
+/// The date at `unit` precision containing `day`.
+fn date_containing(day: DateComplete, unit: StepSize) -> Option<Date> {
+    let y = day.year();
+    let prec = match unit {
+        StepSize::Century => Precision::Century(y),
+        StepSize::Decade => Precision::Decade(y),
+        StepSize::Year => Precision::Year(y),
+        StepSize::Month => Precision::Month(y, day.month()),
+        StepSize::Day => Precision::Day(y, day.month(), day.day()),
+        StepSize::Season => return None,
+    };
+    Date::from_precision_opt(prec)
+}
+
+/// The dates at each of [COMPACT_UNITS] that contain `day`, coarsest first, and that are
+/// `aligned`, e.g. start or end on it.
+fn aligned_dates(day: DateComplete, aligned: impl Fn(&Date) -> bool) -> impl Iterator<Item = Date> {
+    COMPACT_UNITS
+        .iter()
+        // masked negative years count backwards, so they never line up with a range of days
+        .filter(move |&&unit| unit < StepSize::Decade || day.year() >= 0)
+        .filter_map(move |&unit| date_containing(day, unit))
+        .filter(move |d| aligned(d))
+}
+
+/// Whether a date has no unspecified digits in its year. At the end of an open interval, `201X`
+/// would read as some unspecified year, not the whole decade.
+fn unmasked(date: &Date) -> bool {
+    !matches!(
+        date.precision(),
+        Precision::Century(_) | Precision::Decade(_)
+    )
+}
+
+/// The coarsest date without a masked year starting on `first`, for the start of an open
+/// interval. Always succeeds at day precision for a year in range.
+pub(super) fn coarsest_starting(first: DateComplete) -> Option<Date> {
+    aligned_dates(first, |d| d.first_day() == first && unmasked(d)).next()
+}
+
+/// The coarsest date without a masked year ending on `last`. See [coarsest_starting].
+pub(super) fn coarsest_ending(last: DateComplete) -> Option<Date> {
+    aligned_dates(last, |d| d.last_day() == last && unmasked(d)).next()
+}
+
+impl Edtf {
+    /// Expresses an inclusive range of days as the most compact EDTF, e.g. `2019-01-01` to
+    /// `2020-12-31` becomes `2019/2020`, `2019-06-01` to `2019-06-30` becomes `2019-06`, and
+    /// `2019-06-01` to `2020-12-31` becomes `2019-06/2020`.
+    ///
+    /// Returns None if `first` is after `last`, or the years are out of range.
+    pub(crate) fn from_day_range(first: DateComplete, last: DateComplete) -> Option<Self> {
+        if first > last {
+            return None;
+        }
+        if let Some(date) =
+            aligned_dates(first, |d| d.first_day() == first && d.last_day() == last).next()
+        {
+            return Some(Edtf::Date(date));
+        }
+        // each end as coarse as it will go without overlapping the other, as long as neither
+        // needs to be a day, e.g. 2019-06/2020 rather than 2019-06/2020-12
+        let coarse = |d: &Date| !matches!(d.precision(), Precision::Day(..));
+        for start in aligned_dates(first, |d| d.first_day() == first).filter(coarse) {
+            let end = aligned_dates(last, |d| d.last_day() == last)
+                .filter(coarse)
+                .find(|end| start.last_day() < end.first_day());
+            if let Some(end) = end {
+                return Some(Edtf::Interval(start, end));
+            }
+        }
+        let start = date_containing(first, StepSize::Day)?;
+        let end = date_containing(last, StepSize::Day)?;
+        Some(Edtf::Interval(start, end))
+    }
+}
+
+/// One end of the range of days an [Edtf] could be referring to. See [Edtf::bounds].
+///
+/// The `Ord` implementation is chronological.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DayBound {
+    /// Before every day a [DateComplete] can represent. The start of an interval with an open or
+    /// unknown start, or either end of a [YYear] too far in the past.
+    NegativeInfinity,
+    /// A day.
+    Day(DateComplete),
+    /// After every day a [DateComplete] can represent. The end of an interval with an open or
+    /// unknown end, or either end of a [YYear] too far in the future.
+    Infinity,
+}
+
+impl DayBound {
+    /// The day, if this is not infinite.
+    pub fn day(self) -> Option<DateComplete> {
+        match self {
+            DayBound::Day(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    fn year(year: i64, month: u32, day: u32) -> Self {
+        match year.try_into() {
+            Ok(y) if Date::year_in_range(y) => DayBound::Day(DateComplete::from_ymd(y, month, day)),
+            _ if year < 0 => DayBound::NegativeInfinity,
+            _ => DayBound::Infinity,
+        }
+    }
+}
+
+/// # Bounds
+impl Edtf {
+    /// The earliest and latest days this could be referring to.
+    ///
+    /// Unspecified digits, seasons and reduced precision cover every day they could mean, so
+    /// `201X` runs from `2010-01-01` to `2019-12-31`, and winter includes December to February of
+    /// the following year. An open or unknown end of an interval is infinite, since nothing
+    /// limits how early or late it could be. Timestamps are taken to fall on their own calendar
+    /// date, regardless of time zone.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::level_1::{DayBound, Edtf};
+    /// let ymd = |y, m, d| DayBound::Day(DateComplete::from_ymd(y, m, d));
+    ///
+    /// let edtf = Edtf::parse("2019-24").unwrap();
+    /// assert_eq!(edtf.bounds(), (ymd(2019, 12, 1), ymd(2020, 2, 29)));
+    /// let edtf = Edtf::parse("../19XX").unwrap();
+    /// assert_eq!(edtf.bounds(), (DayBound::NegativeInfinity, ymd(1999, 12, 31)));
+    /// ```
+    pub fn bounds(&self) -> (DayBound, DayBound) {
+        let first = |d: &Date| DayBound::Day(d.first_day());
+        let last = |d: &Date| DayBound::Day(d.last_day());
+        match self {
+            Edtf::DateTime(dt) => (DayBound::Day(dt.date()), DayBound::Day(dt.date())),
+            Edtf::Date(d) => (first(d), last(d)),
+            Edtf::YYear(y) => (
+                DayBound::year(y.value(), 1, 1),
+                DayBound::year(y.value(), 12, 31),
+            ),
+            Edtf::Interval(a, b) => (first(a), last(b)),
+            Edtf::IntervalFrom(a, _) => (first(a), DayBound::Infinity),
+            Edtf::IntervalTo(_, b) => (DayBound::NegativeInfinity, last(b)),
+        }
+    }
+}
+
+/// # Membership
+impl Edtf {
+    /// Whether `day` is certainly one of the days this refers to, taking the EDTF literally as in
+    /// [Edtf::bounds], without iterating. Masked digits and seasons cover every day they could
+    /// mean, so `2019-XX-XX` contains every day in 2019. Open and unknown ends both extend
+    /// forever, as they do in [Edtf::bounds], so this agrees with the set operations and
+    /// [EdtfIndex].
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::level_1::Edtf;
+    /// let day = DateComplete::from_ymd(2020, 2, 29);
+    /// assert!(Edtf::parse("2019-24").unwrap().contains(&day));
+    /// assert!(Edtf::parse("2019/..").unwrap().contains(&day));
+    /// assert!(Edtf::parse("2019/").unwrap().contains(&day));
+    /// ```
+    pub fn contains(&self, day: &DateComplete) -> bool {
+        let (first, last) = self.bounds();
+        let day = DayBound::Day(*day);
+        first <= day && day <= last
+    }
+
+    /// Whether a timestamp falls within this. Timestamps are compared as instants, converting
+    /// both to UTC, so `2019-07-15T20:00:00Z` contains `2019-07-15T22:00:00+02:00`. Against
+    /// anything else, a timestamp falls on its own calendar date, as in [Edtf::contains],
+    /// whatever its offset.
+    ///
+    /// An unspecified offset is treated as UTC.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let dt = edtf::level_0::Edtf::parse("2019-07-15T23:00:00-10:00")
+    ///     .ok()
+    ///     .and_then(|edtf| edtf.as_datetime())
+    ///     .unwrap();
+    /// assert!(Edtf::parse("2019-07-15").unwrap().contains_datetime(&dt));
+    /// let utc = Edtf::parse("2019-07-16T09:00:00Z").unwrap();
+    /// assert!(utc.contains_datetime(&dt));
+    /// ```
+    pub fn contains_datetime(&self, datetime: &DateTime) -> bool {
+        match self {
+            Edtf::DateTime(own) => match (own.to_utc_opt(), datetime.to_utc_opt()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+            _ => self.contains(&datetime.date()),
+        }
+    }
+}
+
+/// # Day numbers
+impl Edtf {
+    /// The Julian Day Numbers of the earliest and latest days this could be referring to. See
+    /// [DateComplete::to_jdn]. An open or unknown end of an interval is `None`, as is a
+    /// [YYear] too large to count in days.
+    ///
+    /// Timestamps are taken to fall on their own calendar date, regardless of time zone.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let edtf = Edtf::parse("2000-01/2000-02").unwrap();
+    /// assert_eq!(edtf.jdn_bounds(), (Some(2_451_545), Some(2_451_604)));
+    /// let open = Edtf::parse("2000-01-01/..").unwrap();
+    /// assert_eq!(open.jdn_bounds(), (Some(2_451_545), None));
+    /// ```
+    pub fn jdn_bounds(&self) -> (Option<i64>, Option<i64>) {
+        let first = |d: &Date| Some(d.first_day().to_jdn());
+        let last = |d: &Date| Some(d.last_day().to_jdn());
+        match self {
+            Edtf::DateTime(dt) => {
+                let jdn = dt.date().to_jdn();
+                (Some(jdn), Some(jdn))
+            }
+            Edtf::Date(d) => (first(d), last(d)),
+            Edtf::YYear(y) => yyear_jdn_bounds(y.value()),
+            Edtf::Interval(a, b) => (first(a), last(b)),
+            Edtf::IntervalFrom(a, _) => (first(a), None),
+            Edtf::IntervalTo(_, b) => (None, last(b)),
+        }
+    }
+}
+
+fn yyear_jdn_bounds(year: i64) -> (Option<i64>, Option<i64>) {
+    // keeps days_from_civil well clear of overflow
+    const LIMIT: i64 = i64::MAX / 1000;
+    if !(-LIMIT..=LIMIT).contains(&year) {
+        return (None, None);
+    }
+    let jdn = |m, d| Some(days_from_civil(year, m, d) + crate::JDN_UNIX_EPOCH);
+    (jdn(1, 1), jdn(12, 31))
+}
+
+#[cfg(test)]
+fn days(edtf: &str) -> (DateComplete, DateComplete) {
+    let d = Date::parse(edtf).unwrap();
+    (d.first_day(), d.last_day())
+}
+
+#[test]
+fn first_last_day() {
+    let ymd = DateComplete::from_ymd;
+    assert_eq!(days("2019"), (ymd(2019, 1, 1), ymd(2019, 12, 31)));
+    assert_eq!(days("2019-XX-XX"), (ymd(2019, 1, 1), ymd(2019, 12, 31)));
+    assert_eq!(days("2020-02-XX"), (ymd(2020, 2, 1), ymd(2020, 2, 29)));
+    assert_eq!(days("2019-02"), (ymd(2019, 2, 1), ymd(2019, 2, 28)));
+    assert_eq!(days("201X"), (ymd(2010, 1, 1), ymd(2019, 12, 31)));
+    assert_eq!(days("19XX"), (ymd(1900, 1, 1), ymd(1999, 12, 31)));
+    assert_eq!(days("-001X"), (ymd(-19, 1, 1), ymd(-10, 12, 31)));
+    assert_eq!(days("-0019-XX"), (ymd(-19, 1, 1), ymd(-19, 12, 31)));
+    assert_eq!(days("-0020-02-XX"), (ymd(-20, 2, 1), ymd(-20, 2, 29)));
+    assert_eq!(days("2019-22"), (ymd(2019, 6, 1), ymd(2019, 8, 31)));
+    assert_eq!(days("2019-24"), (ymd(2019, 12, 1), ymd(2020, 2, 29)));
+    assert_eq!(days("2019-06-07~"), (ymd(2019, 6, 7), ymd(2019, 6, 7)));
+}
+
+#[test]
+fn day_range_compact() {
+    let ymd = DateComplete::from_ymd;
+    let compact = |a, b| Edtf::from_day_range(a, b).map(|e| e.to_string());
+    let s = |s: &str| Some(s.to_string());
+    assert_eq!(compact(ymd(2019, 1, 1), ymd(2019, 12, 31)), s("2019"));
+    assert_eq!(compact(ymd(2019, 1, 1), ymd(2020, 12, 31)), s("2019/2020"));
+    assert_eq!(compact(ymd(2019, 6, 1), ymd(2019, 6, 30)), s("2019-06"));
+    assert_eq!(
+        compact(ymd(2019, 6, 1), ymd(2021, 7, 31)),
+        s("2019-06/2021-07")
+    );
+    assert_eq!(
+        compact(ymd(2019, 6, 2), ymd(2019, 6, 30)),
+        s("2019-06-02/2019-06-30")
+    );
+    assert_eq!(compact(ymd(2019, 6, 2), ymd(2019, 6, 2)), s("2019-06-02"));
+    assert_eq!(compact(ymd(1900, 1, 1), ymd(1999, 12, 31)), s("19XX"));
+    assert_eq!(compact(ymd(1910, 1, 1), ymd(1939, 12, 31)), s("191X/193X"));
+    assert_eq!(compact(ymd(-19, 1, 1), ymd(-10, 12, 31)), s("-0019/-0010"));
+    assert_eq!(
+        compact(ymd(2019, 6, 1), ymd(2020, 12, 31)),
+        s("2019-06/2020")
+    );
+    assert_eq!(
+        compact(ymd(2010, 1, 1), ymd(2019, 6, 30)),
+        s("2010/2019-06")
+    );
+    assert_eq!(
+        compact(ymd(2019, 1, 1), ymd(2019, 6, 30)),
+        s("2019-01/2019-06")
+    );
+    assert_eq!(compact(ymd(2019, 1, 2), ymd(2019, 1, 1)), None);
+}
+
+#[test]
+fn day_numbers() {
+    let ymd = DateComplete::from_ymd;
+    assert_eq!(ymd(1858, 11, 17).to_jdn(), 2_400_001);
+    assert_eq!(ymd(1, 1, 1).to_jdn(), 1_721_426);
+    assert_eq!(ymd(0, 12, 31).to_rata_die(), 0);
+    assert_eq!(DateComplete::from_jdn(0), Some(ymd(-4713, 11, 24)));
+    assert_eq!(DateComplete::from_rata_die(738_000), Some(ymd(2021, 7, 29)));
+    let min = i32::MIN >> 4;
+    let max = i32::MAX >> 4;
+    assert!(Date::year_in_range(min) && Date::year_in_range(max));
+    for &date in [ymd(min, 1, 1), ymd(max, 12, 31), ymd(-4, 2, 29)].iter() {
+        assert_eq!(DateComplete::from_jdn(date.to_jdn()), Some(date));
+        assert_eq!(DateComplete::from_rata_die(date.to_rata_die()), Some(date));
+    }
+    assert_eq!(DateComplete::from_jdn(i64::MAX), None);
+    assert_eq!(DateComplete::from_rata_die(i64::MIN), None);
+}
+
+#[test]
+fn jdn_bounds() {
+    let bounds = |s| Edtf::parse(s).unwrap().jdn_bounds();
+    let jdn = |y, m, d| Some(DateComplete::from_ymd(y, m, d).to_jdn());
+    assert_eq!(bounds("2019"), (jdn(2019, 1, 1), jdn(2019, 12, 31)));
+    assert_eq!(bounds("2019-24"), (jdn(2019, 12, 1), jdn(2020, 2, 29)));
+    assert_eq!(bounds("19XX/2019-06"), (jdn(1900, 1, 1), jdn(2019, 6, 30)));
+    assert_eq!(bounds("../2019"), (None, jdn(2019, 12, 31)));
+    assert_eq!(bounds("2019/"), (jdn(2019, 1, 1), None));
+    assert_eq!(
+        bounds("2019-07-15T23:00:00-10:00"),
+        (jdn(2019, 7, 15), jdn(2019, 7, 15))
+    );
+    let y = bounds("Y-170000002");
+    assert_eq!(
+        y,
+        (
+            Some(days_from_civil(-170_000_002, 1, 1) + 2_440_588),
+            Some(days_from_civil(-170_000_002, 12, 31) + 2_440_588)
+        )
+    );
+    assert_eq!(y.1.unwrap() - y.0.unwrap(), 364);
+}
+
+#[test]
+fn bounds() {
+    use DayBound::*;
+    let bounds = |s| Edtf::parse(s).unwrap().bounds();
+    let ymd = |y, m, d| Day(DateComplete::from_ymd(y, m, d));
+    assert_eq!(bounds("2019"), (ymd(2019, 1, 1), ymd(2019, 12, 31)));
+    assert_eq!(bounds("2019-XX-XX"), (ymd(2019, 1, 1), ymd(2019, 12, 31)));
+    assert_eq!(bounds("2019-21~"), (ymd(2019, 3, 1), ymd(2019, 5, 31)));
+    assert_eq!(bounds("-001X"), (ymd(-19, 1, 1), ymd(-10, 12, 31)));
+    assert_eq!(bounds("2019/.."), (ymd(2019, 1, 1), Infinity));
+    assert_eq!(bounds("2019/"), (ymd(2019, 1, 1), Infinity));
+    assert_eq!(bounds("/2019-06"), (NegativeInfinity, ymd(2019, 6, 30)));
+    assert_eq!(
+        bounds("2019-07-15T23:00:00-10:00"),
+        (ymd(2019, 7, 15), ymd(2019, 7, 15))
+    );
+    assert_eq!(
+        bounds("Y-170000"),
+        (ymd(-170000, 1, 1), ymd(-170000, 12, 31))
+    );
+    assert_eq!(
+        bounds("Y-17000000000"),
+        (NegativeInfinity, NegativeInfinity)
+    );
+    assert_eq!(bounds("Y17000000000"), (Infinity, Infinity));
+    assert!(NegativeInfinity < ymd(-170000, 1, 1) && ymd(2019, 1, 1) < Infinity);
+    assert_eq!(Infinity.day(), None);
+}
+
+#[test]
+fn contains() {
+    let ymd = DateComplete::from_ymd;
+    let contains = |s, day| Edtf::parse(s).unwrap().contains(&day);
+    assert!(contains("2019-XX-XX", ymd(2019, 1, 1)));
+    assert!(contains("2019-XX-XX", ymd(2019, 12, 31)));
+    assert!(!contains("2019-XX-XX", ymd(2020, 1, 1)));
+    assert!(contains("201X", ymd(2015, 6, 1)));
+    assert!(contains("2019-24", ymd(2020, 2, 29)));
+    assert!(!contains("2019-24", ymd(2019, 11, 30)));
+    assert!(contains("2019~", ymd(2019, 6, 1)));
+    assert!(!contains("2019~", ymd(2020, 6, 1)));
+    assert!(contains("../2019", ymd(-9999, 1, 1)));
+    // unknown ends extend forever, as in bounds()
+    assert!(contains("/2019", ymd(2019, 1, 1)));
+    assert!(contains("/2019", ymd(1900, 12, 31)));
+    assert!(!contains("/2019", ymd(2020, 1, 1)));
+    assert!(contains("2019/", ymd(2030, 1, 1)));
+    assert!(!contains("2019/", ymd(2018, 12, 31)));
+    assert!(contains("2019/2020-06", ymd(2020, 6, 30)));
+    assert!(!contains("2019/2020-06", ymd(2020, 7, 1)));
+    assert!(contains("2019-07-15T23:00:00-10:00", ymd(2019, 7, 15)));
+    assert!(contains("Y-170000", ymd(-170000, 3, 1)));
+    assert!(!contains("Y-17000000000", ymd(-170000, 3, 1)));
+
+    let dt = |s| match Edtf::parse(s) {
+        Ok(Edtf::DateTime(dt)) => dt,
+        _ => panic!("not a DateTime: {}", s),
+    };
+    let contains = |s, d| Edtf::parse(s).unwrap().contains_datetime(&dt(d));
+    assert!(contains("2019-07-15", "2019-07-15T23:00:00-10:00"));
+    assert!(!contains("2019-07-16", "2019-07-15T23:00:00-10:00"));
+    assert!(contains("2019/..", "2019-07-15T23:00:00Z"));
+    assert!(contains(
+        "2019-07-16T09:00:00Z",
+        "2019-07-15T23:00:00-10:00"
+    ));
+    assert!(contains("2019-07-16T09:00:00", "2019-07-15T23:00:00-10:00"));
+    assert!(contains(
+        "2019-07-16T14:30:00+05:30",
+        "2019-07-16T09:00:00Z"
+    ));
+    assert!(!contains("2019-07-16T09:00:01Z", "2019-07-16T09:00:00Z"));
+}
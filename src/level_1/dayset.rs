@@ -0,0 +1,290 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use core::ops::RangeInclusive;
+
+use super::iter::DayIter;
+use super::*;
+
+/// A set of days, stored as a sorted list of runs of consecutive days, so that a century costs
+/// no more than a single day. Build one from any number of EDTFs or days, and combine them with
+/// [DaySet::union] and [DaySet::intersection] in time proportional to the number of runs.
+///
+/// ```
+/// use edtf::DateComplete;
+/// use edtf::level_1::{DaySet, Edtf};
+/// let set = |s| DaySet::from_edtf(&Edtf::parse(s).unwrap()).unwrap();
+///
+/// let coverage = set("2019-01/2019-03").union(&set("2019-06"));
+/// assert_eq!(coverage.len(), 90 + 30);
+/// let overlap = coverage.intersection(&set("2019-03-15/2019-06-10"));
+/// assert_eq!(overlap.len(), 17 + 10);
+/// assert!(overlap.contains(&DateComplete::from_ymd(2019, 6, 1)));
+/// assert!(!overlap.contains(&DateComplete::from_ymd(2019, 4, 1)));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct DaySet {
+    /// Inclusive runs of days since the epoch, sorted, with gaps between them
+    runs: Vec<(i64, i64)>,
+}
+
+fn to_day(days: i64) -> DateComplete {
+    // runs are only ever made from DateCompletes, so this always fits
+    DateComplete::from_days_since_epoch(days).expect("day in a DaySet out of range")
+}
+
+impl DaySet {
+    /// An empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every day in an EDTF, as in [Edtf::bounds], so masked dates and seasons include every day
+    /// they could mean. None if the EDTF has an open or unknown end, or a [YYear] too large to
+    /// count in days.
+    ///
+    /// ```
+    /// use edtf::level_1::{DaySet, Edtf};
+    /// let days = DaySet::from_edtf(&Edtf::parse("2019-XX-XX").unwrap()).unwrap();
+    /// assert_eq!(days.len(), 365);
+    /// assert_eq!(DaySet::from_edtf(&Edtf::parse("2019/..").unwrap()), None);
+    /// ```
+    pub fn from_edtf(edtf: &Edtf) -> Option<Self> {
+        let mut set = Self::new();
+        if !set.insert_edtf(edtf) {
+            return None;
+        }
+        Some(set)
+    }
+
+    /// Adds every day in an EDTF, as in [DaySet::from_edtf]. Returns false, leaving the set
+    /// unchanged, if the EDTF does not have a finite number of days.
+    pub fn insert_edtf(&mut self, edtf: &Edtf) -> bool {
+        match edtf.bounds() {
+            (DayBound::Day(first), DayBound::Day(last)) => {
+                self.insert_range(first, last);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds a single day.
+    pub fn insert(&mut self, day: DateComplete) {
+        self.insert_range(day, day);
+    }
+
+    /// Adds every day from `first` to `last` inclusive. Does nothing if `last` is before `first`.
+    pub fn insert_range(&mut self, first: DateComplete, last: DateComplete) {
+        let (mut first, mut last) = (first.days_since_epoch(), last.days_since_epoch());
+        if first > last {
+            return;
+        }
+        // runs that overlap or touch the new one are merged into it
+        let start = self.runs.partition_point(|&(_, end)| end + 1 < first);
+        let stop = self.runs.partition_point(|&(begin, _)| begin <= last + 1);
+        if start < stop {
+            first = first.min(self.runs[start].0);
+            last = last.max(self.runs[stop - 1].1);
+        }
+        self.runs.splice(start..stop, Some((first, last)));
+    }
+
+    /// The number of days in the set.
+    pub fn len(&self) -> u64 {
+        self.runs
+            .iter()
+            .map(|&(first, last)| (last - first + 1) as u64)
+            .sum()
+    }
+
+    /// Whether the set has no days.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Whether `day` is in the set.
+    pub fn contains(&self, day: &DateComplete) -> bool {
+        let day = day.days_since_epoch();
+        let index = self.runs.partition_point(|&(_, last)| last < day);
+        matches!(self.runs.get(index), Some(&(first, _)) if first <= day)
+    }
+
+    /// The days in either set.
+    pub fn union(&self, other: &DaySet) -> DaySet {
+        let mut runs: Vec<(i64, i64)> = Vec::with_capacity(self.runs.len() + other.runs.len());
+        let (mut a, mut b) = (self.runs.iter().peekable(), other.runs.iter().peekable());
+        loop {
+            let next = match (a.peek(), b.peek()) {
+                (Some(x), Some(y)) if x <= y => a.next(),
+                (Some(_), Some(_)) => b.next(),
+                (Some(_), None) => a.next(),
+                (None, _) => b.next(),
+            };
+            let &(first, last) = match next {
+                Some(run) => run,
+                None => break,
+            };
+            match runs.last_mut() {
+                Some(prev) if first <= prev.1 + 1 => prev.1 = prev.1.max(last),
+                _ => runs.push((first, last)),
+            }
+        }
+        DaySet { runs }
+    }
+
+    /// The days in both sets.
+    pub fn intersection(&self, other: &DaySet) -> DaySet {
+        let mut runs = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&a), Some(&b)) = (self.runs.get(i), other.runs.get(j)) {
+            let first = a.0.max(b.0);
+            let last = a.1.min(b.1);
+            if first <= last {
+                runs.push((first, last));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        DaySet { runs }
+    }
+
+    /// The runs of consecutive days in the set, in order, as inclusive ranges.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::level_1::{DaySet, Edtf};
+    /// let mut set = DaySet::new();
+    /// set.insert_edtf(&Edtf::parse("2019-06").unwrap());
+    /// set.insert_edtf(&Edtf::parse("2019-07-01").unwrap());
+    /// set.insert_edtf(&Edtf::parse("2019-12-25").unwrap());
+    /// let ranges: Vec<_> = set.ranges().collect();
+    /// assert_eq!(ranges, vec![
+    ///     DateComplete::from_ymd(2019, 6, 1)..=DateComplete::from_ymd(2019, 7, 1),
+    ///     DateComplete::from_ymd(2019, 12, 25)..=DateComplete::from_ymd(2019, 12, 25),
+    /// ]);
+    /// ```
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<DateComplete>> + '_ {
+        self.runs
+            .iter()
+            .map(|&(first, last)| to_day(first)..=to_day(last))
+    }
+
+    /// Iterators over each run of consecutive days in the set, in order.
+    pub fn day_iters(&self) -> impl Iterator<Item = DayIter> + '_ {
+        self.ranges().map(|range| {
+            let (first, last) = range.into_inner();
+            DayIter::from(
+                (first.year(), first.month(), first.day())
+                    ..=(last.year(), last.month(), last.day()),
+            )
+        })
+    }
+
+    /// Every day in the set, in order.
+    pub fn iter(&self) -> impl Iterator<Item = DateComplete> + '_ {
+        self.day_iters().flatten()
+    }
+}
+
+impl Extend<DateComplete> for DaySet {
+    fn extend<I: IntoIterator<Item = DateComplete>>(&mut self, iter: I) {
+        for day in iter {
+            self.insert(day);
+        }
+    }
+}
+
+impl core::iter::FromIterator<DateComplete> for DaySet {
+    fn from_iter<I: IntoIterator<Item = DateComplete>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+#[cfg(test)]
+fn set(strs: &[&str]) -> DaySet {
+    let mut set = DaySet::new();
+    for s in strs {
+        assert!(set.insert_edtf(&Edtf::parse(s).unwrap()));
+    }
+    set
+}
+
+#[cfg(test)]
+fn ranges(set: &DaySet) -> Vec<(String, String)> {
+    set.ranges()
+        .map(|r| (r.start().to_string(), r.end().to_string()))
+        .collect()
+}
+
+#[test]
+fn dayset_insert() {
+    let s = set(&["2019-03", "2019-01", "2019-02-10/2019-02-20"]);
+    assert_eq!(
+        ranges(&s),
+        vec![
+            ("2019-01-01".into(), "2019-01-31".into()),
+            ("2019-02-10".into(), "2019-02-20".into()),
+            ("2019-03-01".into(), "2019-03-31".into()),
+        ]
+    );
+    // bridges all three runs
+    let s = set(&[
+        "2019-03",
+        "2019-01",
+        "2019-02-10/2019-02-20",
+        "2019-01-15/2019-02-28",
+    ]);
+    assert_eq!(ranges(&s), vec![("2019-01-01".into(), "2019-03-31".into())]);
+    assert_eq!(s.len(), 90);
+    assert_eq!(set(&["201X", "2015-24"]).len(), 3652);
+    assert!(set(&[]).is_empty());
+    assert!(DaySet::from_edtf(&Edtf::parse("../2019").unwrap()).is_none());
+    assert!(DaySet::from_edtf(&Edtf::parse("2019/").unwrap()).is_none());
+}
+
+#[test]
+fn dayset_operations() {
+    let a = set(&["2019-01/2019-03", "2019-06", "2020"]);
+    let b = set(&["2019-03-20/2019-06-05", "2019-07", "2020-12-31/2021-01-01"]);
+    assert_eq!(
+        ranges(&a.union(&b)),
+        vec![
+            ("2019-01-01".into(), "2019-07-31".into()),
+            ("2020-01-01".into(), "2021-01-01".into()),
+        ]
+    );
+    assert_eq!(
+        ranges(&a.intersection(&b)),
+        vec![
+            ("2019-03-20".into(), "2019-03-31".into()),
+            ("2019-06-01".into(), "2019-06-05".into()),
+            ("2020-12-31".into(), "2020-12-31".into()),
+        ]
+    );
+    assert_eq!(a.union(&DaySet::new()), a);
+    assert!(a.intersection(&DaySet::new()).is_empty());
+    assert_eq!(a.intersection(&b), b.intersection(&a));
+    assert_eq!(a.union(&b), b.union(&a));
+}
+
+#[test]
+fn dayset_iter() {
+    let edtf = Edtf::parse("2019-12-30/2020-01-02").unwrap();
+    let s = DaySet::from_edtf(&edtf).unwrap();
+    let days: Vec<_> = s.iter().collect();
+    assert_eq!(days, edtf.iter_days().unwrap().collect::<Vec<_>>());
+    let collected: DaySet = days.iter().rev().copied().collect();
+    assert_eq!(collected, s);
+    assert!(s.contains(&DateComplete::from_ymd(2020, 1, 1)));
+    assert!(!s.contains(&DateComplete::from_ymd(2020, 1, 3)));
+    assert!(!s.contains(&DateComplete::from_ymd(2019, 12, 29)));
+}
@@ -0,0 +1,256 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::length::whole_months;
+use super::*;
+use crate::common::{days_from_civil, days_in_month};
+
+/// The time between two days, in whole years, then whole months, then days, the way ages are
+/// counted. From `1850-03-10` to `1910-05-09` is 60 years, 1 month and 29 days.
+///
+/// Formats as an ISO 8601 duration, e.g. `P60Y1M29D`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearsMonthsDays {
+    years: u32,
+    months: u32,
+    days: u32,
+}
+
+impl YearsMonthsDays {
+    /// Whole years.
+    pub fn years(&self) -> u32 {
+        self.years
+    }
+
+    /// Whole months after the years, `0..=11`.
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    /// Days after the months, `0..=30`.
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    /// From the day `start` to the day `end`, in days since the epoch. Zero if `end` is before
+    /// `start`.
+    fn between(start: i64, end: i64) -> Option<Self> {
+        if end <= start {
+            return Some(Self::default());
+        }
+        let months = whole_months(start, end)?;
+        let from = DateComplete::from_days_since_epoch(start)?;
+        let total = from.year() as i64 * 12 + (from.month() as i64 - 1) + months;
+        let year = num_integer::div_floor(total, 12);
+        let month = num_integer::mod_floor(total, 12) as u32 + 1;
+        let day = from
+            .day()
+            .min(days_in_month(year.try_into().ok()?, month as u8) as u32);
+        let anchor = days_from_civil(year, month, day);
+        Some(YearsMonthsDays {
+            years: (months / 12).try_into().ok()?,
+            months: (months % 12) as u32,
+            days: (end - anchor).try_into().ok()?,
+        })
+    }
+}
+
+impl fmt::Display for YearsMonthsDays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "P{}Y{}M{}D", self.years, self.months, self.days)
+    }
+}
+
+/// How much time passed between two [Edtf]s, as returned by [Edtf::elapsed_since].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Elapsed {
+    min: YearsMonthsDays,
+    max: YearsMonthsDays,
+    nominal: YearsMonthsDays,
+    certainty: Certainty,
+}
+
+impl Elapsed {
+    /// The least time that could have passed.
+    pub fn min(&self) -> YearsMonthsDays {
+        self.min
+    }
+
+    /// The most time that could have passed.
+    pub fn max(&self) -> YearsMonthsDays {
+        self.max
+    }
+
+    /// The time between the middle days of the two EDTFs, taken literally.
+    pub fn nominal(&self) -> YearsMonthsDays {
+        self.nominal
+    }
+
+    /// The combined certainty of every date involved, so the result is approximate if either
+    /// EDTF is approximate, and uncertain if either is uncertain.
+    pub fn certainty(&self) -> Certainty {
+        self.certainty
+    }
+}
+
+fn certainty(edtf: &Edtf) -> Certainty {
+    let both = |a: &Date, b: &Date| Certainty::from(a.certainty() as u8 | b.certainty() as u8);
+    match edtf {
+        Edtf::Date(d) => d.certainty(),
+        Edtf::Interval(a, b) => both(a, b),
+        Edtf::IntervalFrom(d, _) | Edtf::IntervalTo(_, d) => d.certainty(),
+        Edtf::DateTime(_) | Edtf::YYear(_) => Certainty::Certain,
+    }
+}
+
+/// The first and last day in a pair of bounds, if both are finite.
+fn days((first, last): (DayBound, DayBound)) -> Option<(i64, i64)> {
+    Some((
+        first.day()?.days_since_epoch(),
+        last.day()?.days_since_epoch(),
+    ))
+}
+
+/// # Elapsed time
+impl Edtf {
+    /// How much time passed from `earlier` to self, under the default [InterpretationPolicy].
+    /// See [InterpretationPolicy::elapsed].
+    ///
+    /// ```
+    /// use edtf::level_1::{Certainty, Edtf};
+    /// let birth = Edtf::parse("1850~").unwrap();
+    /// let death = Edtf::parse("1910-XX").unwrap();
+    /// let age = death.elapsed_since(&birth).unwrap();
+    /// assert_eq!(age.nominal().years(), 60);
+    /// assert_eq!(age.min().to_string(), "P54Y0M1D");
+    /// assert_eq!(age.max().to_string(), "P65Y11M30D");
+    /// assert_eq!(age.certainty(), Certainty::Approximate);
+    /// ```
+    pub fn elapsed_since(&self, earlier: &Edtf) -> Option<Elapsed> {
+        InterpretationPolicy::default().elapsed(earlier, self)
+    }
+}
+
+/// # Elapsed time
+impl InterpretationPolicy {
+    /// How much time passed from `from` to `to`, taking each to be one unknown day within
+    /// [Edtf::lax_bounds]. So masked dates and intervals could be any of their days, and
+    /// approximate dates are widened by this policy's tolerance. The minimum is zero if `to`
+    /// could be on or before `from`.
+    ///
+    /// None if either has an open or unknown end, or a [YYear] too large to count in days, or
+    /// if `to` is certainly before `from`.
+    ///
+    /// ```
+    /// use edtf::level_1::{Edtf, InterpretationPolicy};
+    /// let policy = InterpretationPolicy::exact();
+    /// let from = Edtf::parse("1850-03-10").unwrap();
+    /// let to = Edtf::parse("1910-05-09").unwrap();
+    /// let elapsed = policy.elapsed(&from, &to).unwrap();
+    /// assert_eq!(elapsed.min(), elapsed.max());
+    /// assert_eq!(elapsed.nominal().to_string(), "P60Y1M29D");
+    /// assert_eq!(policy.elapsed(&to, &from), None);
+    /// ```
+    pub fn elapsed(&self, from: &Edtf, to: &Edtf) -> Option<Elapsed> {
+        let (from_first, from_last) = days(from.lax_bounds(self))?;
+        let (to_first, to_last) = days(to.lax_bounds(self))?;
+        if to_last < from_first {
+            return None;
+        }
+        let middle =
+            |edtf: &Edtf| days(edtf.bounds()).map(|(first, last)| first + (last - first) / 2);
+        Some(Elapsed {
+            min: YearsMonthsDays::between(from_last, to_first)?,
+            max: YearsMonthsDays::between(from_first, to_last)?,
+            nominal: YearsMonthsDays::between(middle(from)?, middle(to)?)?,
+            certainty: Certainty::from(certainty(from) as u8 | certainty(to) as u8),
+        })
+    }
+}
+
+#[cfg(test)]
+fn elapsed(from: &str, to: &str) -> Option<(String, String, String, Certainty)> {
+    let from = Edtf::parse(from).unwrap();
+    let e = Edtf::parse(to).unwrap().elapsed_since(&from)?;
+    Some((
+        e.min().to_string(),
+        e.max().to_string(),
+        e.nominal().to_string(),
+        e.certainty(),
+    ))
+}
+
+#[test]
+fn years_months_days() {
+    let ymd = |a: (i32, u32, u32), b: (i32, u32, u32)| {
+        let day = |(y, m, d)| DateComplete::from_ymd(y, m, d).days_since_epoch();
+        YearsMonthsDays::between(day(a), day(b))
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(ymd((1850, 3, 10), (1910, 5, 9)), "P60Y1M29D");
+    assert_eq!(ymd((1850, 3, 10), (1910, 3, 10)), "P60Y0M0D");
+    assert_eq!(ymd((2019, 1, 31), (2019, 3, 1)), "P0Y1M1D");
+    assert_eq!(ymd((2020, 1, 31), (2020, 3, 1)), "P0Y1M1D");
+    assert_eq!(ymd((2019, 1, 31), (2019, 2, 28)), "P0Y0M28D");
+    assert_eq!(ymd((2020, 2, 29), (2021, 2, 28)), "P0Y11M30D");
+    assert_eq!(ymd((2020, 2, 29), (2021, 3, 1)), "P1Y0M1D");
+    assert_eq!(ymd((-1, 12, 31), (1, 1, 1)), "P1Y0M1D");
+    assert_eq!(ymd((2019, 6, 1), (2019, 5, 1)), "P0Y0M0D");
+}
+
+#[test]
+fn elapsed_since() {
+    use Certainty::*;
+    assert_eq!(
+        elapsed("1850~", "1910-XX"),
+        Some((
+            "P54Y0M1D".into(),
+            "P65Y11M30D".into(),
+            "P60Y0M0D".into(),
+            Approximate
+        ))
+    );
+    assert_eq!(
+        elapsed("1850/1852", "1910?"),
+        Some((
+            "P57Y0M1D".into(),
+            "P60Y11M30D".into(),
+            "P59Y0M0D".into(),
+            Uncertain
+        ))
+    );
+    assert_eq!(
+        elapsed("1850~", "1910?"),
+        Some((
+            "P54Y0M1D".into(),
+            "P65Y11M30D".into(),
+            "P60Y0M0D".into(),
+            ApproximateUncertain
+        ))
+    );
+    assert_eq!(
+        elapsed("2019-06", "2019"),
+        Some((
+            "P0Y0M0D".into(),
+            "P0Y6M30D".into(),
+            "P0Y0M17D".into(),
+            Certain
+        ))
+    );
+    assert_eq!(
+        elapsed("2019-06-15T12:00:00Z", "2020-06-15"),
+        Some((
+            "P1Y0M0D".into(),
+            "P1Y0M0D".into(),
+            "P1Y0M0D".into(),
+            Certain
+        ))
+    );
+    assert_eq!(elapsed("2020", "2019"), None);
+    assert_eq!(elapsed("1850/..", "1910"), None);
+    assert_eq!(elapsed("1850", "1910/"), None);
+}
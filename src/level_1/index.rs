@@ -0,0 +1,404 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use std::collections::BTreeSet;
+
+use super::*;
+
+/// Identifies a record in an [EdtfIndex]. Ids of removed records may be reused.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryId(usize);
+
+#[derive(Debug, Clone)]
+struct Entry<T> {
+    edtf: Edtf,
+    value: T,
+    first: DayBound,
+    last: DayBound,
+}
+
+/// Where an entry is filed in the index
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Slot {
+    /// Both ends finite: a bin by the number of days, and the first day
+    Bin(usize, i64),
+    /// Starts at negative infinity, keyed by the last day
+    OpenStart(DayBound),
+    /// Ends at infinity, keyed by the first day
+    OpenEnd(DayBound),
+}
+
+fn days(bound: DayBound) -> i64 {
+    match bound {
+        DayBound::NegativeInfinity => i64::MIN,
+        DayBound::Day(d) => d.days_since_epoch(),
+        DayBound::Infinity => i64::MAX,
+    }
+}
+
+impl Slot {
+    fn of(first: DayBound, last: DayBound) -> Self {
+        match (first, last) {
+            (DayBound::NegativeInfinity, last) => Slot::OpenStart(last),
+            (first, DayBound::Infinity) => Slot::OpenEnd(first),
+            (first, last) => {
+                let (first, last) = (days(first), days(last));
+                // a bin holds lengths from 2^(n - 1) to 2^n - 1 days
+                let length = (last - first + 1) as u64;
+                Slot::Bin(64 - length.leading_zeros() as usize, first)
+            }
+        }
+    }
+}
+
+/// An index of records dated with level 1 EDTF, for finding the ones overlapping, containing,
+/// within, before or after a period, without looking at every record.
+///
+/// Records are indexed by the range of days from [Edtf::bounds], so masked dates and seasons
+/// cover every day they could mean, and open (`..`) or unknown ends of intervals extend forever.
+/// Query results are in order of [EntryId].
+///
+/// ```
+/// use edtf::level_1::{Edtf, EdtfIndex};
+/// let parse = |s| Edtf::parse(s).unwrap();
+/// let mut index = EdtfIndex::new();
+/// index.insert(parse("1912/1913"), "a");
+/// let b = index.insert(parse("1916-07"), "b");
+/// index.insert(parse("1917/.."), "c");
+/// index.insert(parse("1939/1945"), "d");
+///
+/// let war = parse("1914/1918");
+/// let found: Vec<_> = index.overlapping(&war).into_iter().map(|(_, v)| *v).collect();
+/// assert_eq!(found, vec!["b", "c"]);
+/// index.remove(b);
+/// let found: Vec<_> = index.after(&war).into_iter().map(|(_, v)| *v).collect();
+/// assert_eq!(found, vec!["d"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EdtfIndex<T> {
+    entries: Vec<Option<Entry<T>>>,
+    vacant: Vec<usize>,
+    bins: Vec<BTreeSet<(i64, usize)>>,
+    open_start: BTreeSet<(DayBound, usize)>,
+    open_end: BTreeSet<(DayBound, usize)>,
+    len: usize,
+}
+
+impl<T> Default for EdtfIndex<T> {
+    fn default() -> Self {
+        EdtfIndex {
+            entries: Vec::new(),
+            vacant: Vec::new(),
+            bins: Vec::new(),
+            open_start: BTreeSet::new(),
+            open_end: BTreeSet::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> EdtfIndex<T> {
+    /// An empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of records
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no records
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a record, returning an id to get or remove it with.
+    pub fn insert(&mut self, edtf: Edtf, value: T) -> EntryId {
+        let (first, last) = edtf.bounds();
+        let entry = Entry {
+            edtf,
+            value,
+            first,
+            last,
+        };
+        let id = match self.vacant.pop() {
+            Some(id) => {
+                self.entries[id] = Some(entry);
+                id
+            }
+            None => {
+                self.entries.push(Some(entry));
+                self.entries.len() - 1
+            }
+        };
+        match Slot::of(first, last) {
+            Slot::Bin(bin, start) => {
+                if self.bins.len() <= bin {
+                    self.bins.resize_with(bin + 1, BTreeSet::new);
+                }
+                self.bins[bin].insert((start, id));
+            }
+            Slot::OpenStart(last) => {
+                self.open_start.insert((last, id));
+            }
+            Slot::OpenEnd(first) => {
+                self.open_end.insert((first, id));
+            }
+        }
+        self.len += 1;
+        EntryId(id)
+    }
+
+    /// Removes a record, returning it, or None if there is no record with that id.
+    pub fn remove(&mut self, id: EntryId) -> Option<(Edtf, T)> {
+        let entry = self.entries.get_mut(id.0)?.take()?;
+        match Slot::of(entry.first, entry.last) {
+            Slot::Bin(bin, start) => self.bins[bin].remove(&(start, id.0)),
+            Slot::OpenStart(last) => self.open_start.remove(&(last, id.0)),
+            Slot::OpenEnd(first) => self.open_end.remove(&(first, id.0)),
+        };
+        self.vacant.push(id.0);
+        self.len -= 1;
+        Some((entry.edtf, entry.value))
+    }
+
+    /// Gets a record
+    pub fn get(&self, id: EntryId) -> Option<(&Edtf, &T)> {
+        let entry = self.entries.get(id.0)?.as_ref()?;
+        Some((&entry.edtf, &entry.value))
+    }
+
+    /// Iterates over every record, in order of [EntryId].
+    pub fn iter(&self) -> impl Iterator<Item = (EntryId, &Edtf, &T)> {
+        self.entries.iter().enumerate().filter_map(|(id, entry)| {
+            let entry = entry.as_ref()?;
+            Some((EntryId(id), &entry.edtf, &entry.value))
+        })
+    }
+
+    /// Records sharing at least one day with `query`.
+    pub fn overlapping(&self, query: &Edtf) -> Vec<(EntryId, &T)> {
+        let (first, last) = query.bounds();
+        self.search(first, last, |e| e.first <= last && first <= e.last)
+    }
+
+    /// Records that contain every day of `query`.
+    pub fn containing(&self, query: &Edtf) -> Vec<(EntryId, &T)> {
+        let (first, last) = query.bounds();
+        self.search(first, last, |e| e.first <= first && last <= e.last)
+    }
+
+    /// Records entirely within `query`.
+    pub fn within(&self, query: &Edtf) -> Vec<(EntryId, &T)> {
+        let (first, last) = query.bounds();
+        self.search(first, last, |e| first <= e.first && e.last <= last)
+    }
+
+    /// Records that end before `query` starts.
+    pub fn before(&self, query: &Edtf) -> Vec<(EntryId, &T)> {
+        let (first, _) = query.bounds();
+        let mut ids: Vec<usize> = Vec::new();
+        let before = days(first);
+        for bin in &self.bins {
+            ids.extend(bin.range(..(before, 0)).map(|&(_, id)| id));
+        }
+        ids.extend(self.open_start.range(..(first, 0)).map(|&(_, id)| id));
+        self.collect(ids, |e| e.last < first)
+    }
+
+    /// Records that start after `query` ends.
+    pub fn after(&self, query: &Edtf) -> Vec<(EntryId, &T)> {
+        let (_, last) = query.bounds();
+        let mut ids: Vec<usize> = Vec::new();
+        let after = days(last);
+        if after < i64::MAX {
+            for bin in &self.bins {
+                ids.extend(bin.range((after + 1, 0)..).map(|&(_, id)| id));
+            }
+        }
+        ids.extend(self.open_end.range((last, usize::MAX)..).map(|&(_, id)| id));
+        self.collect(ids, |e| last < e.first)
+    }
+
+    /// Every record overlapping the days `first..=last`, filtered by `keep`.
+    fn search(
+        &self,
+        first: DayBound,
+        last: DayBound,
+        keep: impl Fn(&Entry<T>) -> bool,
+    ) -> Vec<(EntryId, &T)> {
+        let mut ids: Vec<usize> = Vec::new();
+        let (from, to) = (days(first), days(last));
+        for (bin, set) in self.bins.iter().enumerate() {
+            // nothing in this bin is longer than this, so nothing starting earlier can reach
+            let longest = (1i64 << bin.min(62)) - 1;
+            let earliest = from.saturating_sub(longest);
+            ids.extend(
+                set.range((earliest, 0)..=(to, usize::MAX))
+                    .map(|&(_, id)| id),
+            );
+        }
+        ids.extend(self.open_start.range((first, 0)..).map(|&(_, id)| id));
+        ids.extend(
+            self.open_end
+                .range(..=(last, usize::MAX))
+                .map(|&(_, id)| id),
+        );
+        self.collect(ids, keep)
+    }
+
+    fn collect(&self, mut ids: Vec<usize>, keep: impl Fn(&Entry<T>) -> bool) -> Vec<(EntryId, &T)> {
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| {
+                let entry = self.entries[id].as_ref()?;
+                Some((EntryId(id), entry)).filter(|(_, e)| keep(e))
+            })
+            .map(|(id, entry)| (id, &entry.value))
+            .collect()
+    }
+}
+
+impl<T> Extend<(Edtf, T)> for EdtfIndex<T> {
+    fn extend<I: IntoIterator<Item = (Edtf, T)>>(&mut self, iter: I) {
+        for (edtf, value) in iter {
+            self.insert(edtf, value);
+        }
+    }
+}
+
+impl<T> core::iter::FromIterator<(Edtf, T)> for EdtfIndex<T> {
+    fn from_iter<I: IntoIterator<Item = (Edtf, T)>>(iter: I) -> Self {
+        let mut index = Self::new();
+        index.extend(iter);
+        index
+    }
+}
+
+#[cfg(test)]
+fn sample_index() -> EdtfIndex<&'static str> {
+    [
+        "1912/1913",
+        "1914-07-28/1918-11-11",
+        "1916-07",
+        "1917/..",
+        "../1914-06",
+        "../1914-07-28",
+        "191X",
+        "1939/1945",
+        "/1850",
+        "1918-24",
+        "Y-170000",
+        "Y170000000000",
+    ]
+    .iter()
+    .map(|&s| (Edtf::parse(s).unwrap(), s))
+    .collect()
+}
+
+#[cfg(test)]
+fn values<T: Copy>(found: Vec<(EntryId, &T)>) -> Vec<T> {
+    found.into_iter().map(|(_, v)| *v).collect()
+}
+
+#[test]
+fn index_queries() {
+    let index = sample_index();
+    let war = Edtf::parse("1914/1918").unwrap();
+    assert_eq!(
+        values(index.overlapping(&war)),
+        vec![
+            "1914-07-28/1918-11-11",
+            "1916-07",
+            "1917/..",
+            "../1914-06",
+            "../1914-07-28",
+            "191X",
+            "1918-24"
+        ]
+    );
+    assert_eq!(
+        values(index.within(&war)),
+        vec!["1914-07-28/1918-11-11", "1916-07"]
+    );
+    assert_eq!(values(index.containing(&war)), vec!["191X"]);
+    let day = Edtf::parse("1916-07-01").unwrap();
+    assert_eq!(
+        values(index.containing(&day)),
+        vec!["1914-07-28/1918-11-11", "1916-07", "191X"]
+    );
+    assert_eq!(
+        values(index.before(&war)),
+        vec!["1912/1913", "/1850", "Y-170000"]
+    );
+    assert_eq!(
+        values(index.after(&war)),
+        vec!["1939/1945", "Y170000000000"]
+    );
+    let open = Edtf::parse("1944/..").unwrap();
+    assert_eq!(
+        values(index.overlapping(&open)),
+        vec!["1917/..", "1939/1945", "Y170000000000"]
+    );
+    assert_eq!(values(index.after(&open)), Vec::<&str>::new());
+}
+
+#[test]
+fn index_insert_remove() {
+    let mut index = sample_index();
+    assert_eq!(index.len(), 12);
+    let war = Edtf::parse("1914/1918").unwrap();
+    let ids: Vec<EntryId> = index
+        .overlapping(&war)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    for &id in &ids {
+        assert!(index.remove(id).is_some());
+        assert!(index.remove(id).is_none());
+    }
+    assert_eq!(index.len(), 5);
+    assert!(index.overlapping(&war).is_empty());
+    let id = index.insert(Edtf::parse("1915").unwrap(), "new");
+    assert!(ids.contains(&id));
+    assert_eq!(values(index.overlapping(&war)), vec!["new"]);
+    assert_eq!(
+        index.get(id).map(|(e, _)| e.to_string()),
+        Some("1915".into())
+    );
+    assert_eq!(index.iter().count(), 6);
+}
+
+#[test]
+fn index_matches_scan() {
+    // compare against checking every record, for a spread of lengths
+    let mut index = EdtfIndex::new();
+    let start = DateComplete::from_ymd(1900, 1, 1);
+    let mut all = Vec::new();
+    for n in 0..600i64 {
+        let first = start.add_days(n * 37 % 20_000).unwrap();
+        let last = first.add_days((n * n * 7) % (1 << (n % 16))).unwrap();
+        let edtf = Edtf::from_day_range(first, last).unwrap();
+        index.insert(edtf, n);
+        all.push((n, edtf));
+    }
+    for q in 0..60i64 {
+        let first = start.add_days(q * 331 % 20_000).unwrap();
+        let last = first.add_days(q * q % 3_000).unwrap();
+        let query = Edtf::from_day_range(first, last).unwrap();
+        let (qf, ql) = query.bounds();
+        let expected: Vec<i64> = all
+            .iter()
+            .filter(|(_, e)| {
+                let (f, l) = e.bounds();
+                f <= ql && qf <= l
+            })
+            .map(|(n, _)| *n)
+            .collect();
+        assert_eq!(values(index.overlapping(&query)), expected);
+    }
+}
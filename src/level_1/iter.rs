@@ -5,7 +5,7 @@
 // Copyright © 2021 Corporation for Digital Scholarship
 
 mod date;
-mod incrementable;
+pub(super) mod incrementable;
 
 use super::{
     packed::{DMFlags, PackedInt},
@@ -309,7 +309,7 @@ pub enum SmallestStep {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
-enum StepSize {
+pub(crate) enum StepSize {
     Day,
     Month,
     Season,
@@ -8,24 +8,14 @@ use super::{
     packed::{DMFlags, DMMask, PackedInt, PackedU8},
     *,
 };
-use crate::common::{MONTH_DAYCOUNT, MONTH_DAYCOUNT_LEAP};
-
-fn days_in_month(y: i32, m: u8) -> u8 {
-    let leap = crate::common::is_leap_year(y);
-    let lut = if leap {
-        MONTH_DAYCOUNT_LEAP
-    } else {
-        MONTH_DAYCOUNT
-    };
-    lut[m as usize - 1]
-}
+use crate::common::days_in_month;
 
 /// # Iterators
 impl Date {
-    pub(super) fn unspec_start(&self, min_level: StepSize) -> Option<Self> {
+    pub(crate) fn unspec_start(&self, min_level: StepSize) -> Option<Self> {
         self.reify_unspecified(1, |_, _| 1, 1, min_level)
     }
-    pub(super) fn unspec_end(&self, min_level: StepSize) -> Option<Self> {
+    pub(crate) fn unspec_end(&self, min_level: StepSize) -> Option<Self> {
         self.reify_unspecified(12, days_in_month, 31, min_level)
     }
     fn reify_unspecified(
@@ -138,6 +138,20 @@ cyclic! {
         }
     )
 }
+cyclic! {
+    pub(crate) struct SeasonOfYear::<u32>(
+        |s| if s >= 24 {
+            Err(21u32)
+        } else {
+            Ok(s + 1)
+        },
+        |s| if s <= 21 {
+            Err(24u32)
+        } else {
+            Ok(s - 1)
+        }
+    )
+}
 use crate::common::MONTH_DAYCOUNT;
 use crate::common::MONTH_DAYCOUNT_LEAP;
 cyclic! {
@@ -237,3 +251,28 @@ incrementable! {
         }
     )
 }
+
+incrementable! {
+    pub(crate) struct YearSeason::<(i32, u32)>(
+        |(year, season)| {
+            let x = match SeasonOfYear::incr_map(season) {
+                Ok(next_season) => (year, next_season),
+                Err(spring) => {
+                    let y = i32::checked_add(year, 1)?;
+                    (y, spring)
+                }
+            };
+            Some(x)
+        },
+        |(year, season)| {
+            let x = match SeasonOfYear::decr_map(season) {
+                Ok(prev_season) => (year, prev_season),
+                Err(winter) => {
+                    let y = i32::checked_sub(year, 1)?;
+                    (y, winter)
+                }
+            };
+            Some(x)
+        }
+    )
+}
@@ -0,0 +1,273 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::allen::Extent;
+use super::*;
+
+/// A whole number of days, months or years, known to be between [Amount::min] and
+/// [Amount::max].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Amount {
+    min: u64,
+    max: Option<u64>,
+}
+
+impl Amount {
+    fn exactly(n: u64) -> Self {
+        Amount {
+            min: n,
+            max: Some(n),
+        }
+    }
+
+    /// The smallest it could be.
+    pub fn min(&self) -> u64 {
+        self.min
+    }
+
+    /// The largest it could be, or None if there is no limit.
+    pub fn max(&self) -> Option<u64> {
+        self.max
+    }
+
+    /// The amount, if it is not ambiguous.
+    pub fn exact(&self) -> Option<u64> {
+        self.max.filter(|&max| max == self.min)
+    }
+
+    fn hull(self, other: Amount) -> Self {
+        Amount {
+            min: self.min.min(other.min),
+            max: self.max.zip(other.max).map(|(a, b)| a.max(b)),
+        }
+    }
+}
+
+/// How long an [Edtf] lasts, in whole days, months and years. Returned by [Edtf::length].
+///
+/// Months and years are calendar months and years, counted from the first day and rounded
+/// down, so `2019-06-15/2020-06-14` is 366 days, 12 months and 1 year, but
+/// `2019-06-15/2020-06-13` is only 11 months and 0 years.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Length {
+    days: Amount,
+    months: Amount,
+    years: Amount,
+}
+
+impl Length {
+    /// The number of days.
+    pub fn days(&self) -> Amount {
+        self.days
+    }
+
+    /// The number of whole months.
+    pub fn months(&self) -> Amount {
+        self.months
+    }
+
+    /// The number of whole years.
+    pub fn years(&self) -> Amount {
+        self.years
+    }
+
+    /// Anything from a single day upwards
+    fn unbounded() -> Self {
+        let zero = Amount { min: 0, max: None };
+        Length {
+            days: Amount { min: 1, max: None },
+            months: zero,
+            years: zero,
+        }
+    }
+
+    /// From the day `start` up to but not including the day `end`, in days since the epoch.
+    fn between(start: i64, end: i64) -> Option<Self> {
+        let months = whole_months(start, end)?;
+        let amount = |n: i64| Amount::exactly(n.max(0) as u64);
+        Some(Length {
+            days: Amount::exactly((end - start).max(1) as u64),
+            months: amount(months),
+            years: amount(months / 12),
+        })
+    }
+
+    fn hull(self, other: Length) -> Self {
+        Length {
+            days: self.days.hull(other.days),
+            months: self.months.hull(other.months),
+            years: self.years.hull(other.years),
+        }
+    }
+}
+
+/// Whole calendar months from the day `start` up to the day `end`.
+pub(super) fn whole_months(start: i64, end: i64) -> Option<i64> {
+    let start = DateComplete::from_days_since_epoch(start)?;
+    let end = DateComplete::from_days_since_epoch(end)?;
+    let months =
+        (end.year() as i64 - start.year() as i64) * 12 + end.month() as i64 - start.month() as i64;
+    Some(if end.day() < start.day() {
+        months - 1
+    } else {
+        months
+    })
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year.rem_euclid(4) == 0 && (year.rem_euclid(100) != 0 || year.rem_euclid(400) == 0)
+}
+
+/// # Length
+impl Edtf {
+    /// How long self lasts, under the default [InterpretationPolicy]. See
+    /// [InterpretationPolicy::length].
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let length = Edtf::parse("2019-XX/2020").unwrap().length().unwrap();
+    /// assert_eq!(length.months().min(), 13);
+    /// assert_eq!(length.months().max(), Some(24));
+    /// assert_eq!(length.years().max(), Some(2));
+    /// let length = Edtf::parse("2019-06").unwrap().length().unwrap();
+    /// assert_eq!(length.days().exact(), Some(30));
+    /// ```
+    pub fn length(&self) -> Option<Length> {
+        InterpretationPolicy::default().length(self)
+    }
+}
+
+/// # Length
+impl InterpretationPolicy {
+    /// How long `edtf` lasts, as a range when it is ambiguous. None if it has an open end, and
+    /// so lasts forever.
+    ///
+    /// A masked date is one unknown year, month or day, so `201X` lasts 365 or 366 days. The
+    /// ends of an interval can be any day in their range, shifted by this policy's tolerance
+    /// when approximate, so `2019-XX/2020` lasts between 13 and 24 months. An unknown end can
+    /// be anywhere, so `2019/` lasts at least a day, with no upper limit. Shifting a single
+    /// approximate date does not change how long it lasts. A timestamp lasts one day.
+    ///
+    /// ```
+    /// use edtf::level_1::{Edtf, InterpretationPolicy};
+    /// let edtf = Edtf::parse("1950~/1960").unwrap();
+    /// let length = InterpretationPolicy::exact().length(&edtf).unwrap();
+    /// assert_eq!(length.years().exact(), Some(11));
+    /// let length = InterpretationPolicy::default().length(&edtf).unwrap();
+    /// assert_eq!((length.years().min(), length.years().max()), (6, Some(16)));
+    /// assert_eq!(InterpretationPolicy::default().length(&Edtf::parse("2019/..").unwrap()), None);
+    /// ```
+    pub fn length(&self, edtf: &Edtf) -> Option<Length> {
+        let start = |d: &Date| Extent::hull(&Extent::of_date(d, self)).start;
+        let end = |d: &Date| Extent::hull(&Extent::of_date(d, self)).end;
+        match edtf {
+            Edtf::Date(d) => {
+                let candidates: Option<Vec<Length>> =
+                    Extent::of_date(d, &InterpretationPolicy::exact())
+                        .into_iter()
+                        .map(|e| Length::between(e.start.0, e.end.0))
+                        .collect();
+                candidates?.into_iter().reduce(Length::hull)
+            }
+            Edtf::DateTime(_) => Length::between(0, 1),
+            Edtf::YYear(y) => {
+                let days = if is_leap_year(y.value()) { 366 } else { 365 };
+                Some(Length {
+                    days: Amount::exactly(days),
+                    months: Amount::exactly(12),
+                    years: Amount::exactly(1),
+                })
+            }
+            Edtf::Interval(a, b) => {
+                let (start, end) = (start(a), end(b));
+                let shortest = Length::between(start.1, end.0)?;
+                let longest = Length::between(start.0, end.1)?;
+                Some(shortest.hull(longest))
+            }
+            Edtf::IntervalFrom(_, Terminal::Unknown) | Edtf::IntervalTo(Terminal::Unknown, _) => {
+                Some(Length::unbounded())
+            }
+            Edtf::IntervalFrom(_, Terminal::Open) | Edtf::IntervalTo(Terminal::Open, _) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+fn lengths(s: &str) -> Option<[(u64, Option<u64>); 3]> {
+    let length = Edtf::parse(s).unwrap().length()?;
+    let pair = |a: Amount| (a.min(), a.max());
+    Some([
+        pair(length.days()),
+        pair(length.months()),
+        pair(length.years()),
+    ])
+}
+
+#[test]
+fn length_of_dates() {
+    assert_eq!(
+        lengths("2019"),
+        Some([(365, Some(365)), (12, Some(12)), (1, Some(1))])
+    );
+    assert_eq!(
+        lengths("201X"),
+        Some([(365, Some(366)), (12, Some(12)), (1, Some(1))])
+    );
+    assert_eq!(
+        lengths("2019-24"),
+        Some([(91, Some(91)), (3, Some(3)), (0, Some(0))])
+    );
+    assert_eq!(
+        lengths("2019-XX"),
+        Some([(28, Some(31)), (1, Some(1)), (0, Some(0))])
+    );
+    assert_eq!(
+        lengths("2019-06-XX"),
+        Some([(1, Some(1)), (0, Some(0)), (0, Some(0))])
+    );
+    assert_eq!(
+        lengths("2019-06-15T12:00:00Z"),
+        Some([(1, Some(1)), (0, Some(0)), (0, Some(0))])
+    );
+    assert_eq!(
+        lengths("Y-170000001"),
+        Some([(365, Some(365)), (12, Some(12)), (1, Some(1))])
+    );
+    assert_eq!(
+        lengths("Y170000"),
+        Some([(366, Some(366)), (12, Some(12)), (1, Some(1))])
+    );
+}
+
+#[test]
+fn length_of_intervals() {
+    assert_eq!(
+        lengths("2019-XX/2020"),
+        Some([(397, Some(731)), (13, Some(24)), (1, Some(2))])
+    );
+    assert_eq!(
+        lengths("2019-06-15/2020-06-14"),
+        Some([(366, Some(366)), (12, Some(12)), (1, Some(1))])
+    );
+    assert_eq!(
+        lengths("2019-06-15/2020-06-13"),
+        Some([(365, Some(365)), (11, Some(11)), (0, Some(0))])
+    );
+    assert_eq!(
+        lengths("19XX/20XX"),
+        Some([(731, Some(73049)), (24, Some(2400)), (2, Some(200))])
+    );
+    // approximate ends are shifted by a year either side of 2019 and 2020
+    let policy = InterpretationPolicy::default().with_year(Tolerance::Years(1));
+    let length = policy.length(&Edtf::parse("2019~/2020~").unwrap()).unwrap();
+    assert_eq!(length.years().min(), 0);
+    assert_eq!(length.years().max(), Some(4));
+    assert_eq!(length.days().min(), 1);
+    assert_eq!(lengths("2019/"), Some([(1, None), (0, None), (0, None)]));
+    assert_eq!(lengths("/2019"), Some([(1, None), (0, None), (0, None)]));
+    assert_eq!(lengths("2019/.."), None);
+    assert_eq!(lengths("../2019"), None);
+}
@@ -11,6 +11,23 @@ mod parser;
 mod validate;
 
 mod basic;
+mod bounds;
+pub use bounds::DayBound;
+mod allen;
+pub use allen::AllenRelation;
+mod policy;
+pub use policy::{InterpretationPolicy, Tolerance};
+pub use span::Span;
+mod dayset;
+mod elapsed;
+mod index;
+mod length;
+mod sets;
+mod span;
+pub use dayset::DaySet;
+pub use elapsed::{Elapsed, YearsMonthsDays};
+pub use index::{EdtfIndex, EntryId};
+pub use length::{Amount, Length};
 
 /// A set of iterators for stepping through date intervals.
 pub mod iter;
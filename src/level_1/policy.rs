@@ -0,0 +1,341 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::*;
+use crate::common::days_in_month;
+
+/// How far an approximate date could be from what is written. See [InterpretationPolicy].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Tolerance {
+    /// Taken literally
+    Exact,
+    /// Plus or minus this many days
+    Days(u32),
+    /// Plus or minus this many months. Days past the end of a shorter month are clamped to its
+    /// last day, and a latest day at the end of a month stays at the end of a month.
+    Months(u32),
+    /// Plus or minus this many years. 29 February becomes 28 February in common years.
+    Years(u32),
+}
+
+impl Tolerance {
+    /// Moves a day by this tolerance, forwards if `later` is true, otherwise backwards. None on
+    /// overflow.
+    pub(super) fn shift(self, day: DateComplete, later: bool) -> Option<DateComplete> {
+        let sign = if later { 1 } else { -1 };
+        let months = match self {
+            Tolerance::Exact => return Some(day),
+            Tolerance::Days(n) => {
+                return day
+                    .add_days(sign * n as i64)
+                    .filter(|d| Date::year_in_range(d.year()))
+            }
+            Tolerance::Months(n) => n as i64,
+            Tolerance::Years(n) => n as i64 * 12,
+        };
+        let index = day.year() as i64 * 12 + day.month() as i64 - 1 + sign * months;
+        let year: i32 = index.div_euclid(12).try_into().ok()?;
+        if !Date::year_in_range(year) {
+            return None;
+        }
+        let month = index.rem_euclid(12) as u32 + 1;
+        let last = days_in_month(year, month as u8) as u32;
+        // the end of a month stays at the end of a month when widening forwards
+        let end_of_month = day.day() == days_in_month(day.year(), day.month() as u8) as u32;
+        let d = if later && end_of_month {
+            last
+        } else {
+            day.day().min(last)
+        };
+        Some(DateComplete::from_ymd(year, month, d))
+    }
+}
+
+/// Gives approximate (`~`) dates a numeric meaning, for widening them consistently, e.g. when
+/// searching. The tolerance depends on the precision of the date, so by default `1950~` means
+/// 1945 to 1955, but `1950-06~` means April to August 1950.
+///
+/// | Precision            | Default tolerance |
+/// |----------------------|-------------------|
+/// | Century, e.g. `19XX` | ± 25 years        |
+/// | Decade, e.g. `195X`  | ± 5 years         |
+/// | Year                 | ± 5 years         |
+/// | Season               | ± 3 months        |
+/// | Month                | ± 2 months        |
+/// | Day                  | ± 3 days          |
+///
+/// Dates with unspecified months or days, like `1950-XX~`, use the tolerance of their most
+/// precise specified component. Dates that are both approximate and uncertain (`%`) are widened
+/// like approximate ones. Uncertain (`?`) dates are taken literally unless
+/// [InterpretationPolicy::with_uncertain_as_approximate] is set.
+///
+/// ```
+/// use edtf::DateComplete;
+/// use edtf::level_1::{DayBound, Edtf, InterpretationPolicy, Tolerance};
+/// let ymd = |y, m, d| DayBound::Day(DateComplete::from_ymd(y, m, d));
+///
+/// let policy = InterpretationPolicy::default();
+/// let edtf = Edtf::parse("1950~").unwrap();
+/// assert_eq!(edtf.bounds(), (ymd(1950, 1, 1), ymd(1950, 12, 31)));
+/// assert_eq!(edtf.lax_bounds(&policy), (ymd(1945, 1, 1), ymd(1955, 12, 31)));
+///
+/// let policy = policy.with_month(Tolerance::Months(1));
+/// let edtf = Edtf::parse("1950-06~").unwrap();
+/// assert_eq!(edtf.lax_bounds(&policy), (ymd(1950, 5, 1), ymd(1950, 7, 31)));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct InterpretationPolicy {
+    century: Tolerance,
+    decade: Tolerance,
+    year: Tolerance,
+    season: Tolerance,
+    month: Tolerance,
+    day: Tolerance,
+    uncertain_as_approximate: bool,
+}
+
+impl Default for InterpretationPolicy {
+    fn default() -> Self {
+        InterpretationPolicy {
+            century: Tolerance::Years(25),
+            decade: Tolerance::Years(5),
+            year: Tolerance::Years(5),
+            season: Tolerance::Months(3),
+            month: Tolerance::Months(2),
+            day: Tolerance::Days(3),
+            uncertain_as_approximate: false,
+        }
+    }
+}
+
+impl InterpretationPolicy {
+    /// A policy that takes every date literally, so [Edtf::lax_bounds] is the same as
+    /// [Edtf::bounds].
+    pub fn exact() -> Self {
+        InterpretationPolicy {
+            century: Tolerance::Exact,
+            decade: Tolerance::Exact,
+            year: Tolerance::Exact,
+            season: Tolerance::Exact,
+            month: Tolerance::Exact,
+            day: Tolerance::Exact,
+            uncertain_as_approximate: false,
+        }
+    }
+
+    /// Sets the tolerance for dates like `19XX~`
+    pub fn with_century(mut self, tolerance: Tolerance) -> Self {
+        self.century = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for dates like `195X~`
+    pub fn with_decade(mut self, tolerance: Tolerance) -> Self {
+        self.decade = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for dates like `1950~`
+    pub fn with_year(mut self, tolerance: Tolerance) -> Self {
+        self.year = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for dates like `1950-21~`
+    pub fn with_season(mut self, tolerance: Tolerance) -> Self {
+        self.season = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for dates like `1950-06~`
+    pub fn with_month(mut self, tolerance: Tolerance) -> Self {
+        self.month = tolerance;
+        self
+    }
+
+    /// Sets the tolerance for dates like `1950-06-01~`
+    pub fn with_day(mut self, tolerance: Tolerance) -> Self {
+        self.day = tolerance;
+        self
+    }
+
+    /// Whether to widen uncertain (`?`) dates as if they were approximate.
+    pub fn with_uncertain_as_approximate(mut self, uncertain_as_approximate: bool) -> Self {
+        self.uncertain_as_approximate = uncertain_as_approximate;
+        self
+    }
+
+    /// The tolerance for an approximate date with this precision.
+    pub fn tolerance(&self, precision: Precision) -> Tolerance {
+        match precision {
+            Precision::Century(_) => self.century,
+            Precision::Decade(_) => self.decade,
+            Precision::Year(_) | Precision::MonthOfYear(_) | Precision::DayOfYear(_) => self.year,
+            Precision::Season(..) => self.season,
+            Precision::Month(..) | Precision::DayOfMonth(..) => self.month,
+            Precision::Day(..) => self.day,
+        }
+    }
+
+    /// The tolerance this policy gives a date, taking its certainty into account.
+    pub(super) fn tolerance_for(&self, date: &Date) -> Tolerance {
+        match date.certainty() {
+            Certainty::Certain => Tolerance::Exact,
+            Certainty::Uncertain if !self.uncertain_as_approximate => Tolerance::Exact,
+            _ => self.tolerance(date.precision()),
+        }
+    }
+
+    fn first(&self, date: &Date) -> DayBound {
+        match self.tolerance_for(date).shift(date.first_day(), false) {
+            Some(day) => DayBound::Day(day),
+            None => DayBound::NegativeInfinity,
+        }
+    }
+
+    fn last(&self, date: &Date) -> DayBound {
+        match self.tolerance_for(date).shift(date.last_day(), true) {
+            Some(day) => DayBound::Day(day),
+            None => DayBound::Infinity,
+        }
+    }
+}
+
+/// # Bounds
+impl Edtf {
+    /// The earliest and latest days this could be referring to, widening approximate dates by
+    /// the tolerances in `policy`. This is the lax, or outer, range; [Edtf::bounds] is the
+    /// strict, or inner, range, which takes every date literally.
+    ///
+    /// Each end of an interval is widened according to its own precision and certainty.
+    /// Timestamps and [YYear]s have no qualifiers, so are never widened.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::level_1::{DayBound, Edtf, InterpretationPolicy};
+    /// let ymd = |y, m, d| DayBound::Day(DateComplete::from_ymd(y, m, d));
+    ///
+    /// let policy = InterpretationPolicy::default();
+    /// let edtf = Edtf::parse("1950-06~/1960").unwrap();
+    /// assert_eq!(edtf.lax_bounds(&policy), (ymd(1950, 4, 1), ymd(1960, 12, 31)));
+    /// ```
+    pub fn lax_bounds(&self, policy: &InterpretationPolicy) -> (DayBound, DayBound) {
+        match self {
+            Edtf::Date(d) => (policy.first(d), policy.last(d)),
+            Edtf::Interval(a, b) => (policy.first(a), policy.last(b)),
+            Edtf::IntervalFrom(a, _) => (policy.first(a), DayBound::Infinity),
+            Edtf::IntervalTo(_, b) => (DayBound::NegativeInfinity, policy.last(b)),
+            Edtf::DateTime(_) | Edtf::YYear(_) => self.bounds(),
+        }
+    }
+}
+
+/// # Membership
+impl Edtf {
+    /// Whether `day` could be one of the days this refers to, under the default
+    /// [InterpretationPolicy]. See [InterpretationPolicy::may_contain].
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// use edtf::level_1::Edtf;
+    /// let edtf = Edtf::parse("1950~").unwrap();
+    /// let day = DateComplete::from_ymd(1953, 6, 1);
+    /// assert!(!edtf.contains(&day));
+    /// assert!(edtf.may_contain(&day));
+    /// ```
+    pub fn may_contain(&self, day: &DateComplete) -> bool {
+        InterpretationPolicy::default().may_contain(self, day)
+    }
+}
+
+impl InterpretationPolicy {
+    /// Whether `day` could be one of the days `edtf` refers to, i.e. whether it is within
+    /// [Edtf::lax_bounds]. Unlike [Edtf::contains], approximate dates are widened by this
+    /// policy's tolerance.
+    pub fn may_contain(&self, edtf: &Edtf, day: &DateComplete) -> bool {
+        let (first, last) = edtf.lax_bounds(self);
+        let day = DayBound::Day(*day);
+        first <= day && day <= last
+    }
+}
+
+#[test]
+fn lax_bounds() {
+    let ymd = |y, m, d| DayBound::Day(DateComplete::from_ymd(y, m, d));
+    let policy = InterpretationPolicy::default();
+    let lax = |s| Edtf::parse(s).unwrap().lax_bounds(&policy);
+    assert_eq!(lax("1950"), (ymd(1950, 1, 1), ymd(1950, 12, 31)));
+    assert_eq!(lax("1950?"), (ymd(1950, 1, 1), ymd(1950, 12, 31)));
+    assert_eq!(lax("1950~"), (ymd(1945, 1, 1), ymd(1955, 12, 31)));
+    assert_eq!(lax("1950%"), (ymd(1945, 1, 1), ymd(1955, 12, 31)));
+    assert_eq!(lax("1950-XX~"), (ymd(1945, 1, 1), ymd(1955, 12, 31)));
+    assert_eq!(lax("195X~"), (ymd(1945, 1, 1), ymd(1964, 12, 31)));
+    assert_eq!(lax("19XX~"), (ymd(1875, 1, 1), ymd(2024, 12, 31)));
+    assert_eq!(lax("1950-24~"), (ymd(1950, 9, 1), ymd(1951, 5, 31)));
+    assert_eq!(lax("1950-06~"), (ymd(1950, 4, 1), ymd(1950, 8, 31)));
+    assert_eq!(lax("1950-01~"), (ymd(1949, 11, 1), ymd(1950, 3, 31)));
+    assert_eq!(lax("1950-06-XX~"), (ymd(1950, 4, 1), ymd(1950, 8, 31)));
+    assert_eq!(lax("1950-12-30~"), (ymd(1950, 12, 27), ymd(1951, 1, 2)));
+    assert_eq!(lax("1950~/1960-06-15"), (ymd(1945, 1, 1), ymd(1960, 6, 15)));
+    assert_eq!(lax("1950~/.."), (ymd(1945, 1, 1), DayBound::Infinity));
+    assert_eq!(
+        lax("/1950~"),
+        (DayBound::NegativeInfinity, ymd(1955, 12, 31))
+    );
+    assert_eq!(
+        lax("1950-06-15T12:00:00Z"),
+        (ymd(1950, 6, 15), ymd(1950, 6, 15))
+    );
+
+    let policy = InterpretationPolicy::default()
+        .with_uncertain_as_approximate(true)
+        .with_month(Tolerance::Days(10))
+        .with_year(Tolerance::Years(1));
+    let lax = |s| Edtf::parse(s).unwrap().lax_bounds(&policy);
+    assert_eq!(lax("1950?"), (ymd(1949, 1, 1), ymd(1951, 12, 31)));
+    assert_eq!(lax("1950-06?"), (ymd(1950, 5, 22), ymd(1950, 7, 10)));
+    let exact = InterpretationPolicy::exact();
+    let edtf = Edtf::parse("1950-06~").unwrap();
+    assert_eq!(edtf.lax_bounds(&exact), edtf.bounds());
+}
+
+#[test]
+fn tolerance_clamps() {
+    let ymd = DateComplete::from_ymd;
+    let shift = |t: Tolerance, d, later| t.shift(d, later);
+    assert_eq!(
+        shift(Tolerance::Years(1), ymd(2020, 2, 29), true),
+        Some(ymd(2021, 2, 28))
+    );
+    assert_eq!(
+        shift(Tolerance::Months(1), ymd(2019, 3, 31), false),
+        Some(ymd(2019, 2, 28))
+    );
+    assert_eq!(
+        shift(Tolerance::Months(14), ymd(1, 1, 31), false),
+        Some(ymd(-1, 11, 30))
+    );
+    let max = ymd(i32::MAX >> 4, 12, 31);
+    assert_eq!(shift(Tolerance::Years(1), max, true), None);
+    let edtf = Edtf::Date(Date::from_complete(max).and_certainty(Certainty::Approximate));
+    let policy = InterpretationPolicy::default();
+    assert_eq!(edtf.lax_bounds(&policy).1, DayBound::Infinity);
+}
+
+#[test]
+fn may_contain() {
+    let ymd = DateComplete::from_ymd;
+    let may = |s, day| Edtf::parse(s).unwrap().may_contain(&day);
+    assert!(may("1950~", ymd(1945, 1, 1)));
+    assert!(!may("1950~", ymd(1944, 12, 31)));
+    assert!(may("1950-06-XX~", ymd(1950, 8, 31)));
+    assert!(may("2019/", ymd(2030, 1, 1)));
+    assert!(may("/2019", ymd(1000, 1, 1)));
+    assert!(!may("1950?", ymd(1951, 1, 1)));
+    let policy = InterpretationPolicy::default().with_uncertain_as_approximate(true);
+    let edtf = Edtf::parse("1950?").unwrap();
+    assert!(policy.may_contain(&edtf, &ymd(1951, 1, 1)));
+}
@@ -0,0 +1,219 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::bounds::{coarsest_ending, coarsest_starting};
+use super::*;
+
+/// An inclusive range of days, from [Edtf::bounds].
+type DayRange = (DayBound, DayBound);
+
+fn next_day(bound: DayBound) -> DayBound {
+    match bound {
+        DayBound::Day(d) => d
+            .add_days(1)
+            .filter(|d| Date::year_in_range(d.year()))
+            .map_or(DayBound::Infinity, DayBound::Day),
+        other => other,
+    }
+}
+
+fn previous_day(bound: DayBound) -> DayBound {
+    match bound {
+        DayBound::Day(d) => d
+            .add_days(-1)
+            .filter(|d| Date::year_in_range(d.year()))
+            .map_or(DayBound::NegativeInfinity, DayBound::Day),
+        other => other,
+    }
+}
+
+/// The range of days in an EDTF, or None for a [YYear] too large to count in days.
+fn day_range(edtf: &Edtf) -> Option<DayRange> {
+    let (first, last) = edtf.bounds();
+    if first == DayBound::Infinity || last == DayBound::NegativeInfinity {
+        return None;
+    }
+    Some((first, last))
+}
+
+/// Writes a range of days as compactly as possible. None if it is the whole timeline, or it has a year
+/// level 1 can only write as a whole [YYear].
+fn range_to_edtf((first, last): DayRange) -> Option<Edtf> {
+    let in_range = |d: &DateComplete| !helpers::outside_9999(d.year() as i64);
+    match (first.day(), last.day()) {
+        (Some(first), Some(last)) if in_range(&first) && in_range(&last) => {
+            Edtf::from_day_range(first, last)
+        }
+        (Some(first), Some(last)) => {
+            let whole_year = first.year() == last.year()
+                && (first.month(), first.day()) == (1, 1)
+                && (last.month(), last.day()) == (12, 31);
+            if !whole_year {
+                return None;
+            }
+            YYear::new_opt(first.year() as i64).map(Edtf::YYear)
+        }
+        (Some(first), None) if in_range(&first) => Some(Edtf::IntervalFrom(
+            coarsest_starting(first)?,
+            Terminal::Open,
+        )),
+        (None, Some(last)) if in_range(&last) => {
+            Some(Edtf::IntervalTo(Terminal::Open, coarsest_ending(last)?))
+        }
+        _ => None,
+    }
+}
+
+fn ranges_to_edtfs(ranges: impl IntoIterator<Item = DayRange>) -> Option<Vec<Edtf>> {
+    ranges.into_iter().map(range_to_edtf).collect()
+}
+
+/// # Set operations
+///
+/// These treat an EDTF as the range of days from [Edtf::bounds], so masked dates and seasons
+/// cover every day they could mean, and open or unknown ends of intervals extend forever. The
+/// results are written as compactly as possible, with any infinite ends written open (`..`).
+/// When the result is not one contiguous range, it is a list of ranges in chronological order;
+/// when it is empty, the list is empty.
+///
+/// They return None when the result cannot be written in level 1 EDTF, i.e. the whole timeline,
+/// or a range with years outside `-9999..=9999` that is not exactly one [YYear].
+impl Edtf {
+    /// The days in both self and `other`.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let a = Edtf::parse("2019/2021").unwrap();
+    /// let b = Edtf::parse("2020-06/2022").unwrap();
+    /// assert_eq!(a.intersection(&b), Some(vec![Edtf::parse("2020-06/2021").unwrap()]));
+    /// let c = Edtf::parse("2023").unwrap();
+    /// assert_eq!(a.intersection(&c), Some(vec![]));
+    /// ```
+    pub fn intersection(&self, other: &Edtf) -> Option<Vec<Edtf>> {
+        let (a, b) = (day_range(self)?, day_range(other)?);
+        let first = a.0.max(b.0);
+        let last = a.1.min(b.1);
+        if first > last {
+            return Some(Vec::new());
+        }
+        ranges_to_edtfs(Some((first, last)))
+    }
+
+    /// The days in either self or `other`.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let parse = |s| Edtf::parse(s).unwrap();
+    /// let a = parse("2019-01/2019-06");
+    /// assert_eq!(a.union(&parse("2019-07")), Some(vec![parse("2019-01/2019-07")]));
+    /// assert_eq!(
+    ///     a.union(&parse("2020")),
+    ///     Some(vec![parse("2019-01/2019-06"), parse("2020")])
+    /// );
+    /// ```
+    pub fn union(&self, other: &Edtf) -> Option<Vec<Edtf>> {
+        let (a, b) = (day_range(self)?, day_range(other)?);
+        let (earlier, later) = if a <= b { (a, b) } else { (b, a) };
+        if later.0 <= next_day(earlier.1) {
+            ranges_to_edtfs(Some((earlier.0, earlier.1.max(later.1))))
+        } else {
+            ranges_to_edtfs(vec![earlier, later])
+        }
+    }
+
+    /// The days in self but not in `other`.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let parse = |s| Edtf::parse(s).unwrap();
+    /// let a = parse("2019/2021");
+    /// assert_eq!(
+    ///     a.difference(&parse("2020-03")),
+    ///     Some(vec![parse("2019/2020-02"), parse("2020-04/2021")])
+    /// );
+    /// assert_eq!(a.difference(&parse("2020/..")), Some(vec![parse("2019")]));
+    /// ```
+    pub fn difference(&self, other: &Edtf) -> Option<Vec<Edtf>> {
+        let (a, b) = (day_range(self)?, day_range(other)?);
+        let mut ranges = Vec::with_capacity(2);
+        if a.0 < b.0 {
+            ranges.push((a.0, a.1.min(previous_day(b.0))));
+        }
+        if b.1 < a.1 {
+            ranges.push((a.0.max(next_day(b.1)), a.1));
+        }
+        ranges.retain(|(first, last)| first <= last);
+        ranges_to_edtfs(ranges)
+    }
+}
+
+#[cfg(test)]
+fn strings(edtfs: Option<Vec<Edtf>>) -> Option<Vec<String>> {
+    edtfs.map(|v| v.iter().map(|e| e.to_string()).collect())
+}
+
+#[cfg(test)]
+fn op(f: fn(&Edtf, &Edtf) -> Option<Vec<Edtf>>, a: &str, b: &str) -> Option<Vec<String>> {
+    strings(f(&Edtf::parse(a).unwrap(), &Edtf::parse(b).unwrap()))
+}
+
+#[cfg(test)]
+fn some(strs: &[&str]) -> Option<Vec<String>> {
+    Some(strs.iter().map(|s| s.to_string()).collect())
+}
+
+#[test]
+fn intersections() {
+    let and = |a, b| op(Edtf::intersection, a, b);
+    assert_eq!(and("2019/2021", "2020-06/2022"), some(&["2020-06/2021"]));
+    assert_eq!(and("2020-06/2022", "2019/2021"), some(&["2020-06/2021"]));
+    assert_eq!(and("2019", "2019-24"), some(&["2019-12"]));
+    assert_eq!(
+        and("201X", "2015-03-04/2030"),
+        some(&["2015-03-04/2019-12-31"])
+    );
+    assert_eq!(and("2019", "2020"), some(&[]));
+    assert_eq!(and("2019/..", "../2019-06"), some(&["2019-01/2019-06"]));
+    assert_eq!(and("2019/..", "2021/"), some(&["2021/.."]));
+    assert_eq!(and("../2019", "/2019-06-15"), some(&["../2019-06-15"]));
+    assert_eq!(and("2019-07-15T23:00:00Z", "2019"), some(&["2019-07-15"]));
+    assert_eq!(and("Y-170000", "Y-170000"), some(&["Y-170000"]));
+    assert_eq!(and("Y-17000000000", "2019"), None);
+}
+
+#[test]
+fn unions() {
+    let or = |a, b| op(Edtf::union, a, b);
+    assert_eq!(or("2019", "2020"), some(&["2019/2020"]));
+    assert_eq!(or("2019-06", "2019"), some(&["2019"]));
+    assert_eq!(or("2019", "2021"), some(&["2019", "2021"]));
+    assert_eq!(or("2021", "2019"), some(&["2019", "2021"]));
+    assert_eq!(or("2019-24", "2020-03/2020-05"), some(&["2019-12/2020-05"]));
+    assert_eq!(or("2019/..", "2010"), some(&["2010", "2019/.."]));
+    assert_eq!(or("2019/..", "2010/2018"), some(&["2010/.."]));
+    assert_eq!(or("../2019", "2018/.."), None);
+    assert_eq!(or("Y-170000", "2019"), some(&["Y-170000", "2019"]));
+    assert_eq!(or("Y-170000", "Y-170001"), None);
+}
+
+#[test]
+fn differences() {
+    let minus = |a, b| op(Edtf::difference, a, b);
+    assert_eq!(
+        minus("2019/2021", "2020-03"),
+        some(&["2019/2020-02", "2020-04/2021"])
+    );
+    assert_eq!(
+        minus("2019/2021", "2021-06-15/.."),
+        some(&["2019-01-01/2021-06-14"])
+    );
+    assert_eq!(minus("2019", "2018/2020"), some(&[]));
+    assert_eq!(minus("2019", "2021"), some(&["2019"]));
+    assert_eq!(minus("2019/..", "2020"), some(&["2019", "2021/.."]));
+    assert_eq!(minus("../2019", "2020"), some(&["../2019"]));
+    assert_eq!(minus("../2019", "2010/2030"), some(&["../2009"]));
+    assert_eq!(minus("2019-24", "2020"), some(&["2019-12"]));
+}
@@ -0,0 +1,206 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+use super::iter::incrementable::{Decrementable, YearMonth, YearSeason};
+use super::*;
+use crate::common::days_in_month;
+
+/// A calendar amount to shift a [Date] by. See [Date::checked_add].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Span {
+    /// A number of days
+    Days(u32),
+    /// A number of calendar months
+    Months(u32),
+    /// A number of seasons, i.e. three months each
+    Seasons(u32),
+    /// A number of years
+    Years(u32),
+    /// A number of decades
+    Decades(u32),
+    /// A number of centuries
+    Centuries(u32),
+}
+
+impl Span {
+    /// The span in months, or None for days.
+    fn months(self) -> Option<u64> {
+        let (n, months) = match self {
+            Span::Days(_) => return None,
+            Span::Months(n) => (n, 1),
+            Span::Seasons(n) => (n, 3),
+            Span::Years(n) => (n, 12),
+            Span::Decades(n) => (n, 120),
+            Span::Centuries(n) => (n, 1200),
+        };
+        Some(n as u64 * months)
+    }
+}
+
+/// `months` as a whole number of units `unit` months long, if it is one.
+fn whole(months: u64, unit: u64) -> Option<u64> {
+    Some(months / unit).filter(|n| n * unit == months)
+}
+
+/// Steps `n` times forward or back. Only used for fewer steps than make a year.
+fn step<I: Decrementable>(input: I::Input, n: u64, forward: bool) -> Option<I::Output> {
+    let mut storage = I::lift(input);
+    for _ in 0..n {
+        storage = if forward {
+            I::increment(storage)
+        } else {
+            I::decrement(storage)
+        }?;
+    }
+    I::output(storage)
+}
+
+fn add_years(year: i32, years: u64, forward: bool) -> Option<i32> {
+    let years: i32 = years.try_into().ok()?;
+    if forward {
+        year.checked_add(years)
+    } else {
+        year.checked_sub(years)
+    }
+}
+
+fn add_months((year, month): (i32, u32), months: u64, forward: bool) -> Option<(i32, u32)> {
+    let year = add_years(year, months / 12, forward)?;
+    step::<YearMonth>((year, month), months % 12, forward)
+}
+
+/// # Arithmetic
+impl Date {
+    /// Shifts self later by a calendar amount, keeping its precision, masks and certainty, so
+    /// `2019-XX~` plus a year is `2020-XX~`, and `2019-24` plus a season is `2020-21`.
+    ///
+    /// The span must be a whole number of the date's smallest unit: months and seasons can be
+    /// added to `2019-06`, but only whole years to `2019` or `2019-XX`, and only whole decades
+    /// to `201X`. Days can only be added to complete dates. Adding months or years to a day
+    /// that does not exist in the resulting month gives the last day of that month.
+    ///
+    /// None if the span does not fit the date's precision, or the result is out of range.
+    ///
+    /// ```
+    /// use edtf::level_1::{Date, Span};
+    /// let add = |s, span| Date::parse(s).unwrap().checked_add(span).map(|d| d.to_string());
+    /// assert_eq!(add("2019-XX~", Span::Years(1)).as_deref(), Some("2020-XX~"));
+    /// assert_eq!(add("193X", Span::Decades(1)).as_deref(), Some("194X"));
+    /// assert_eq!(add("2019-22", Span::Seasons(1)).as_deref(), Some("2019-23"));
+    /// assert_eq!(add("2019-01-31?", Span::Months(1)).as_deref(), Some("2019-02-28?"));
+    /// assert_eq!(add("2019", Span::Months(1)), None);
+    /// ```
+    pub fn checked_add(&self, span: Span) -> Option<Date> {
+        self.shift(span, true)
+    }
+
+    /// Shifts self earlier by a calendar amount. See [Date::checked_add].
+    ///
+    /// ```
+    /// use edtf::level_1::{Date, Span};
+    /// let sub = |s, span| Date::parse(s).unwrap().checked_sub(span).map(|d| d.to_string());
+    /// assert_eq!(sub("2019-21", Span::Seasons(1)).as_deref(), Some("2018-24"));
+    /// assert_eq!(sub("2020-03-01", Span::Days(1)).as_deref(), Some("2020-02-29"));
+    /// ```
+    pub fn checked_sub(&self, span: Span) -> Option<Date> {
+        self.shift(span, false)
+    }
+
+    fn shift(&self, span: Span, forward: bool) -> Option<Date> {
+        use Precision as P;
+        let (precision, certainty) = self.precision_certainty();
+        let months = match (precision, span) {
+            (P::Day(y, m, d), Span::Days(n)) => {
+                let n = n as i64;
+                let day =
+                    DateComplete::from_ymd_opt(y, m, d)?.add_days(if forward { n } else { -n })?;
+                let date = Date::from_precision_opt(P::Day(day.year(), day.month(), day.day()))?;
+                return Some(date.and_certainty(certainty));
+            }
+            (_, span) => span.months()?,
+        };
+        let years = |y, unit| add_years(y, whole(months, unit)?, forward);
+        let shifted = match precision {
+            P::Century(y) => P::Century(add_years(y, whole(months, 1200)? * 100, forward)?),
+            P::Decade(y) => P::Decade(add_years(y, whole(months, 120)? * 10, forward)?),
+            P::Year(y) => P::Year(years(y, 12)?),
+            P::MonthOfYear(y) => P::MonthOfYear(years(y, 12)?),
+            P::DayOfYear(y) => P::DayOfYear(years(y, 12)?),
+            P::Season(y, s) => {
+                let seasons = whole(months, 3)?;
+                let y = add_years(y, seasons / 4, forward)?;
+                let (y, s) = step::<YearSeason>((y, s as u32), seasons % 4, forward)?;
+                P::Season(y, Season::from_u32_opt(s)?)
+            }
+            P::Month(y, m) => {
+                let (y, m) = add_months((y, m), months, forward)?;
+                P::Month(y, m)
+            }
+            P::DayOfMonth(y, m) => {
+                let (y, m) = add_months((y, m), months, forward)?;
+                P::DayOfMonth(y, m)
+            }
+            P::Day(y, m, d) => {
+                let (y, m) = add_months((y, m), months, forward)?;
+                P::Day(y, m, d.min(days_in_month(y, m as u8) as u32))
+            }
+        };
+        Some(Date::from_precision_opt(shifted)?.and_certainty(certainty))
+    }
+}
+
+#[cfg(test)]
+fn shifted(s: &str, span: Span, forward: bool) -> Option<String> {
+    let date = Date::parse(s).unwrap();
+    let date = if forward {
+        date.checked_add(span)
+    } else {
+        date.checked_sub(span)
+    };
+    date.map(|d| d.to_string())
+}
+
+#[test]
+fn date_checked_add() {
+    let add = |s, span| shifted(s, span, true);
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(add("2019-XX~", Span::Years(1)), some("2020-XX~"));
+    assert_eq!(add("2019-XX-XX%", Span::Months(24)), some("2021-XX-XX%"));
+    assert_eq!(add("193X", Span::Decades(1)), some("194X"));
+    assert_eq!(add("193X", Span::Years(20)), some("195X"));
+    assert_eq!(add("19XX", Span::Centuries(1)), some("20XX"));
+    assert_eq!(add("2019-22", Span::Seasons(1)), some("2019-23"));
+    assert_eq!(add("2019-24", Span::Seasons(1)), some("2020-21"));
+    assert_eq!(add("2019-23", Span::Seasons(9)), some("2021-24"));
+    assert_eq!(add("2019-23", Span::Months(6)), some("2020-21"));
+    assert_eq!(add("2019-11", Span::Months(3)), some("2020-02"));
+    assert_eq!(add("2019-11-XX", Span::Seasons(1)), some("2020-02-XX"));
+    assert_eq!(add("2019-12-31", Span::Days(1)), some("2020-01-01"));
+    assert_eq!(add("2020-02-29", Span::Years(1)), some("2021-02-28"));
+    assert_eq!(add("2019-08-31", Span::Months(13)), some("2020-09-30"));
+    assert_eq!(add("2019", Span::Months(0)), some("2019"));
+    assert_eq!(add("2019", Span::Months(1)), None);
+    assert_eq!(add("2019-22", Span::Months(1)), None);
+    assert_eq!(add("193X", Span::Years(5)), None);
+    assert_eq!(add("2019-06", Span::Days(1)), None);
+    assert_eq!(add("2019", Span::Years(u32::MAX)), None);
+    assert_eq!(add("2019", Span::Centuries(u32::MAX)), None);
+}
+
+#[test]
+fn date_checked_sub() {
+    let sub = |s, span| shifted(s, span, false);
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(sub("2020-XX?", Span::Years(1)), some("2019-XX?"));
+    assert_eq!(sub("194X", Span::Decades(1)), some("193X"));
+    assert_eq!(sub("2019-21", Span::Seasons(1)), some("2018-24"));
+    assert_eq!(sub("2019-21", Span::Seasons(5)), some("2017-24"));
+    assert_eq!(sub("2019-02", Span::Months(14)), some("2017-12"));
+    assert_eq!(sub("2020-03-31", Span::Months(1)), some("2020-02-29"));
+    assert_eq!(sub("2020-03-01", Span::Days(1)), some("2020-02-29"));
+    assert_eq!(sub("0001", Span::Years(2)), some("-0001"));
+    assert_eq!(sub("2019", Span::Seasons(1)), None);
+}
@@ -13,21 +13,42 @@
 #[cfg(all(doc, feature = "chrono"))]
 use chrono::NaiveDate;
 
+mod arithmetic;
 pub(crate) mod common;
 pub(crate) mod helpers;
 mod level0;
 #[allow(missing_docs)]
 mod level2;
 pub mod level_1;
+pub use arithmetic::{IsoWeek, Weekday};
 use common::{UnvalidatedTime, UnvalidatedTz};
 pub use level0::api as level_0;
 #[doc(hidden)]
 pub use level2::api as level_2;
 
+pub mod biblatex;
+pub mod deep_time;
+pub mod era;
+pub mod fiscal;
+pub mod ical;
+pub mod julian;
+pub mod marc;
+pub mod osm;
+pub mod radiocarbon;
+pub mod regnal;
+
+#[cfg(feature = "calendars")]
+#[cfg_attr(docsrs, doc(cfg(feature = "calendars")))]
+pub mod calendars;
+
 #[cfg(feature = "chrono")]
 #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
 mod chrono_interop;
 
+#[cfg(feature = "icu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "icu")))]
+mod icu_interop;
+
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 mod serde_interop;
@@ -102,6 +123,22 @@ impl DateTime {
         self.time.offset()
     }
 
+    /// Shifts this timestamp to UTC, by subtracting its offset. An unspecified offset is treated
+    /// as UTC already. Returns None if the date overflows.
+    pub(crate) fn to_utc_opt(self) -> Option<Self> {
+        let minutes =
+            self.time.hh as i64 * 60 + self.time.mm as i64 - self.time.tz.offset_minutes() as i64;
+        let date = self.date.add_days(minutes.div_euclid(24 * 60))?;
+        let minutes = minutes.rem_euclid(24 * 60);
+        let time = Time {
+            hh: (minutes / 60) as u8,
+            mm: (minutes % 60) as u8,
+            ss: self.time.ss,
+            tz: TzOffset::Utc,
+        };
+        Some(DateTime { date, time })
+    }
+
     #[cfg(feature = "chrono")]
     #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
     #[cfg_attr(not(feature = "chrono"), allow(rustdoc::broken_intra_doc_links))]
@@ -157,7 +194,9 @@ impl DateTime {
 
 /// A structure to hold the date portion of a [DateTime]. It contains a valid date in the proleptic
 /// Gregorian calendar.
-#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+///
+/// The `Ord` implementation is chronological.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DateComplete {
     pub(crate) year: i32,
     pub(crate) month: NonZeroU8,
@@ -181,6 +220,11 @@ impl fmt::Display for DateComplete {
     }
 }
 
+/// The Julian Day Number of 1970-01-01.
+pub(crate) const JDN_UNIX_EPOCH: i64 = 2_440_588;
+/// The Rata Die number of 1970-01-01.
+const RD_UNIX_EPOCH: i64 = 719_163;
+
 impl DateComplete {
     /// Create a complete date. Panics if the date is invalid.
     pub fn from_ymd(year: i32, month: u32, day: u32) -> Self {
@@ -207,6 +251,52 @@ impl DateComplete {
     pub fn day(&self) -> u32 {
         self.day.get() as u32
     }
+
+    /// Days since 1970-01-01.
+    pub(crate) fn days_since_epoch(&self) -> i64 {
+        common::days_from_civil(self.year as i64, self.month(), self.day())
+    }
+
+    /// Inverse of [DateComplete::days_since_epoch]. None if the year does not fit in an i32.
+    pub(crate) fn from_days_since_epoch(days: i64) -> Option<Self> {
+        let (year, month, day) = common::civil_from_days(days);
+        Self::from_ymd_opt(year.try_into().ok()?, month, day)
+    }
+
+    /// The Julian Day Number of this date, i.e. the number of days since 24 November 4714 BC
+    /// (`-4713-11-24`) in the proleptic Gregorian calendar. This is the JDN of the day, not a
+    /// Julian Date, so it is not offset by half a day.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// assert_eq!(DateComplete::from_ymd(2000, 1, 1).to_jdn(), 2_451_545);
+    /// assert_eq!(DateComplete::from_ymd(-4713, 11, 24).to_jdn(), 0);
+    /// ```
+    pub fn to_jdn(&self) -> i64 {
+        self.days_since_epoch() + JDN_UNIX_EPOCH
+    }
+
+    /// Converts a Julian Day Number to a date. Returns None if the year does not fit in an i32.
+    pub fn from_jdn(jdn: i64) -> Option<Self> {
+        Self::from_days_since_epoch(jdn.checked_sub(JDN_UNIX_EPOCH)?)
+    }
+
+    /// The Rata Die number of this date, where `0001-01-01` is day 1, as used in Reingold and
+    /// Dershowitz's *Calendrical Calculations*.
+    ///
+    /// ```
+    /// use edtf::DateComplete;
+    /// assert_eq!(DateComplete::from_ymd(1, 1, 1).to_rata_die(), 1);
+    /// assert_eq!(DateComplete::from_ymd(1970, 1, 1).to_rata_die(), 719_163);
+    /// ```
+    pub fn to_rata_die(&self) -> i64 {
+        self.days_since_epoch() + RD_UNIX_EPOCH
+    }
+
+    /// Converts a Rata Die number to a date. Returns None if the year does not fit in an i32.
+    pub fn from_rata_die(rd: i64) -> Option<Self> {
+        Self::from_days_since_epoch(rd.checked_sub(RD_UNIX_EPOCH)?)
+    }
 }
 
 /// The time portion of a [DateTime].
@@ -300,6 +390,17 @@ pub enum TzOffset {
     Minutes(i32),
 }
 
+impl TzOffset {
+    /// The offset from UTC in minutes. Unspecified is treated as UTC.
+    pub(crate) fn offset_minutes(&self) -> i32 {
+        match *self {
+            TzOffset::Unspecified | TzOffset::Utc => 0,
+            TzOffset::Hours(h) => h * 60,
+            TzOffset::Minutes(min) => min,
+        }
+    }
+}
+
 #[cfg_attr(not(feature = "chrono"), allow(rustdoc::broken_intra_doc_links))]
 /// A helper trait for getting timezone information from some value. (Especially [chrono::DateTime]
 /// or [chrono::NaiveDateTime].)
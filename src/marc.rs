@@ -0,0 +1,548 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # MARC 21 008/06-14 dates
+//!
+//! The fixed-length `008` field of a MARC 21 bibliographic record carries a type of date code at
+//! position 06, and two four-character years, Date1 (07-10) and Date2 (11-14). Unknown digits
+//! are written as `u`, e.g. `19uu`, and unused dates are blank.
+//!
+//! | 008/06-14   | EDTF            |
+//! | ---------   | ----            |
+//! | `s1984    ` | `1984`          |
+//! | `s198u    ` | `198X`          |
+//! | `s19uu    ` | `19XX`          |
+//! | `s1uuu    ` | `10XX/19XX`     |
+//! | `e19840315` | `1984-03-15`    |
+//! | `e198403uu` | `1984-03`       |
+//! | `m19841990` | `1984/1990`     |
+//! | `m19849999` | `1984/..`       |
+//! | `m1984uuuu` | `1984/`         |
+//! | `i19101950` | `1910/1950`     |
+//! | `k19201930` | `1920/1930`     |
+//! | `q19601969` | `196X?`         |
+//! | `q19631963` | `1963?`         |
+//! | `q19631966` | none            |
+//! | `u1984uuuu` | `1984/`         |
+//! | `c19849999` | `1984/..`       |
+//! | `d19841990` | `1984/1990`     |
+//! | `nuuuuuuuu` | none            |
+//! | `|||||||||` | none            |
+//!
+//! Level 1 can only leave the last one or two digits of a year unspecified, so a date with
+//! three unknown digits becomes an interval of centuries.
+//!
+//! A questionable date (`q`) is one date that falls somewhere between Date1 and Date2. Level 1
+//! can only express that when the two years span exactly one decade or century, as an
+//! uncertain masked year like `196X?`. Any other range, like `q19631966`, has no level 1
+//! equivalent: `1963?/1966?` would be an interval lasting from 1963 to 1966, so it converts to
+//! none rather than changing its meaning.
+//!
+//! Field 046 needs no conversion, as it can carry EDTF directly with `$2 edtf`.
+//!
+//! ```
+//! use edtf::level_1::Edtf;
+//! use edtf::marc::Marc008Dates;
+//!
+//! let dates = Marc008Dates::parse("q19601969").unwrap();
+//! assert_eq!(dates.to_edtf(), Some(Edtf::parse("196X?").unwrap()));
+//! assert_eq!(Marc008Dates::parse("q19631966").unwrap().to_edtf(), None);
+//!
+//! let edtf = Edtf::parse("196X").unwrap();
+//! assert_eq!(edtf.to_marc_008().unwrap().to_string(), "s196u    ");
+//! ```
+
+use core::fmt;
+
+use crate::level_1::{Certainty, Date, Edtf, Precision, Terminal};
+use crate::ParseError;
+
+/// The type of date code at 008/06.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DateType {
+    /// `s`: single known or probable date
+    Single,
+    /// `e`: detailed date, with the month and day in Date2
+    Detailed,
+    /// `m`: multiple dates, i.e. a range
+    Multiple,
+    /// `q`: questionable date; the date lies somewhere between Date1 and Date2
+    Questionable,
+    /// `i`: inclusive dates of a collection
+    Inclusive,
+    /// `k`: range of years of the bulk of a collection
+    Bulk,
+    /// `c`: continuing resource, currently published
+    Continuing,
+    /// `d`: continuing resource, ceased publication
+    Ceased,
+    /// `u`: continuing resource, status unknown
+    ContinuingUnknown,
+    /// `n`: dates unknown
+    Unknown,
+    /// `|`: no attempt to code
+    NoAttempt,
+}
+
+impl DateType {
+    /// The MARC code for this type of date.
+    pub fn code(self) -> char {
+        match self {
+            DateType::Single => 's',
+            DateType::Detailed => 'e',
+            DateType::Multiple => 'm',
+            DateType::Questionable => 'q',
+            DateType::Inclusive => 'i',
+            DateType::Bulk => 'k',
+            DateType::Continuing => 'c',
+            DateType::Ceased => 'd',
+            DateType::ContinuingUnknown => 'u',
+            DateType::Unknown => 'n',
+            DateType::NoAttempt => '|',
+        }
+    }
+
+    /// Looks up a MARC type of date code. Returns None for codes this crate does not convert,
+    /// like `b` (BC dates) or `r` (reprint dates).
+    pub fn from_code(code: char) -> Option<Self> {
+        Some(match code {
+            's' => DateType::Single,
+            'e' => DateType::Detailed,
+            'm' => DateType::Multiple,
+            'q' => DateType::Questionable,
+            'i' => DateType::Inclusive,
+            'k' => DateType::Bulk,
+            'c' => DateType::Continuing,
+            'd' => DateType::Ceased,
+            'u' => DateType::ContinuingUnknown,
+            'n' => DateType::Unknown,
+            '|' => DateType::NoAttempt,
+            _ => return None,
+        })
+    }
+}
+
+/// 008/06-14: a type of date code, Date1 and Date2. See the [module level
+/// documentation](crate::marc).
+///
+/// The [Display](fmt::Display) implementation writes the nine characters of 008/06-14.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Marc008Dates {
+    date_type: DateType,
+    date1: [u8; 4],
+    date2: [u8; 4],
+}
+
+/// A Date1 or Date2 year.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Year {
+    /// A year, possibly with its last one or two digits unknown
+    Known(Date),
+    /// `1uuu`; the first year of the millennium
+    Millennium(i32),
+    /// `uuuu`
+    Unknown,
+    /// `9999`, i.e. still going
+    Ongoing,
+    /// blanks or fill characters
+    Blank,
+}
+
+impl Year {
+    fn parse(bytes: [u8; 4]) -> Result<Self, ParseError> {
+        if bytes.iter().all(|&b| b == b' ' || b == b'|') {
+            return Ok(Year::Blank);
+        }
+        if &bytes == b"9999" {
+            return Ok(Year::Ongoing);
+        }
+        let n_digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+        if bytes[n_digits..].iter().any(|&b| b != b'u') {
+            return Err(ParseError::Invalid);
+        }
+        let mut year = 0;
+        for &b in &bytes[..n_digits] {
+            year = year * 10 + (b - b'0') as i32;
+        }
+        let year = year * 10i32.pow(4 - n_digits as u32);
+        let prec = match n_digits {
+            4 => Precision::Year(year),
+            3 => Precision::Decade(year),
+            2 => Precision::Century(year),
+            1 => return Ok(Year::Millennium(year)),
+            _ => return Ok(Year::Unknown),
+        };
+        Date::from_precision_opt(prec)
+            .map(Year::Known)
+            .ok_or(ParseError::OutOfRange)
+    }
+
+    fn first(self) -> Option<Date> {
+        match self {
+            Year::Known(d) => Some(d),
+            Year::Millennium(y) => Date::from_precision_opt(Precision::Century(y)),
+            _ => None,
+        }
+    }
+
+    fn last(self) -> Option<Date> {
+        match self {
+            Year::Known(d) => Some(d),
+            Year::Millennium(y) => Date::from_precision_opt(Precision::Century(y + 900)),
+            _ => None,
+        }
+    }
+}
+
+impl Marc008Dates {
+    /// Parses 008/06-14. The input may be just those nine characters, or the whole 40 character
+    /// 008 field.
+    ///
+    /// ```
+    /// use edtf::marc::{DateType, Marc008Dates};
+    /// let field = "850423s1984    nyu           000 0 eng  ";
+    /// let dates = Marc008Dates::parse(field).unwrap();
+    /// assert_eq!(dates.date_type(), DateType::Single);
+    /// assert_eq!(dates.date1(), "1984");
+    /// assert_eq!(dates.date2(), "    ");
+    /// ```
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let bytes = input.as_bytes();
+        let bytes = match bytes.len() {
+            9 => bytes,
+            40 => &bytes[6..15],
+            _ => return Err(ParseError::Invalid),
+        };
+        let date_type = DateType::from_code(bytes[0] as char).ok_or(ParseError::Invalid)?;
+        let mut date1 = [0u8; 4];
+        let mut date2 = [0u8; 4];
+        date1.copy_from_slice(&bytes[1..5]);
+        date2.copy_from_slice(&bytes[5..9]);
+        let dates = Marc008Dates {
+            date_type,
+            date1,
+            date2,
+        };
+        dates.check()?;
+        Ok(dates)
+    }
+
+    fn check(&self) -> Result<(), ParseError> {
+        let d1 = Year::parse(self.date1)?;
+        match self.date_type {
+            // Date2 is not a year here
+            DateType::Detailed => {
+                let (m, d) = self.detailed_month_day()?;
+                return match d1 {
+                    Year::Known(y) if y.precision() == Precision::Year(y.year()) => {
+                        Date::from_ymd_opt(y.year(), m, d)
+                            .map(|_| ())
+                            .ok_or(ParseError::OutOfRange)
+                    }
+                    _ => Err(ParseError::Invalid),
+                };
+            }
+            DateType::Unknown | DateType::NoAttempt => {}
+            _ if d1 == Year::Blank || d1 == Year::Ongoing => return Err(ParseError::Invalid),
+            _ => {}
+        }
+        let d2 = Year::parse(self.date2)?;
+        match (d1.first(), d2.last()) {
+            (Some(a), Some(b)) if a.first_day() > b.last_day() => Err(ParseError::OutOfRange),
+            _ => Ok(()),
+        }
+    }
+
+    /// Date2 of a detailed date is `mmdd`, where the day may be `uu` or blank.
+    fn detailed_month_day(&self) -> Result<(u32, u32), ParseError> {
+        let digits = |b: &[u8]| -> Option<u32> {
+            match b {
+                [a, b] if a.is_ascii_digit() && b.is_ascii_digit() => {
+                    Some(((a - b'0') * 10 + (b - b'0')) as u32)
+                }
+                _ => None,
+            }
+        };
+        let month = digits(&self.date2[..2]).ok_or(ParseError::Invalid)?;
+        let day = match &self.date2[2..] {
+            b"uu" | b"  " => 0,
+            dd => digits(dd).filter(|&d| d != 0).ok_or(ParseError::Invalid)?,
+        };
+        if month == 0 {
+            return Err(ParseError::Invalid);
+        }
+        Ok((month, day))
+    }
+
+    /// The type of date code at 008/06.
+    pub fn date_type(&self) -> DateType {
+        self.date_type
+    }
+
+    /// Date1, 008/07-10.
+    pub fn date1(&self) -> &str {
+        core::str::from_utf8(&self.date1).unwrap_or("")
+    }
+
+    /// Date2, 008/11-14.
+    pub fn date2(&self) -> &str {
+        core::str::from_utf8(&self.date2).unwrap_or("")
+    }
+
+    /// Converts to a level 1 EDTF. Returns None when the dates are unknown (`n`, or both years
+    /// `uuuu`) or not coded (`|`), or for a questionable date (`q`) whose range level 1 cannot
+    /// express as one date. See the [module documentation](crate::marc).
+    pub fn to_edtf(&self) -> Option<Edtf> {
+        // already validated
+        let d1 = Year::parse(self.date1).ok()?;
+        let d2 = Year::parse(self.date2).unwrap_or(Year::Blank);
+        let single = |y: Year| match y {
+            Year::Millennium(_) => Some(Edtf::Interval(y.first()?, y.last()?)),
+            _ => y.first().map(Edtf::Date),
+        };
+        let range = |d1: Year, d2: Year| match (d1, d2) {
+            (Year::Unknown, Year::Unknown) => None,
+            (_, Year::Blank) => single(d1),
+            (_, Year::Ongoing) => Some(Edtf::IntervalFrom(d1.first()?, Terminal::Open)),
+            (_, Year::Unknown) => Some(Edtf::IntervalFrom(d1.first()?, Terminal::Unknown)),
+            (Year::Unknown, _) => Some(Edtf::IntervalTo(Terminal::Unknown, d2.last()?)),
+            _ => Some(Edtf::Interval(d1.first()?, d2.last()?)),
+        };
+        match self.date_type {
+            DateType::Single => single(d1),
+            DateType::Detailed => {
+                let (m, d) = self.detailed_month_day().ok()?;
+                Date::from_ymd_opt(d1.first()?.year(), m, d).map(Edtf::Date)
+            }
+            DateType::Multiple | DateType::Inclusive | DateType::Bulk | DateType::Ceased => {
+                range(d1, d2)
+            }
+            DateType::Continuing => range(d1, Year::Ongoing),
+            DateType::ContinuingUnknown => range(d1, Year::Unknown),
+            DateType::Questionable => {
+                // one date somewhere in the range, which only a masked year can express
+                let one_of = match (d1, d2) {
+                    (Year::Known(d), Year::Blank) => d,
+                    (Year::Known(a), Year::Known(b)) if a == b => a,
+                    (Year::Known(a), Year::Known(b)) => {
+                        let (first, last) = (a.year(), b.year());
+                        let prec = match last.checked_sub(first)? {
+                            9 if first % 10 == 0 => Precision::Decade(first),
+                            99 if first % 100 == 0 => Precision::Century(first),
+                            _ => return None,
+                        };
+                        if (a.precision(), b.precision())
+                            != (Precision::Year(first), Precision::Year(last))
+                        {
+                            return None;
+                        }
+                        Date::from_precision_opt(prec)?
+                    }
+                    _ => return None,
+                };
+                Some(Edtf::Date(one_of.and_certainty(Certainty::Uncertain)))
+            }
+            DateType::Unknown | DateType::NoAttempt => None,
+        }
+    }
+}
+
+impl fmt::Display for Marc008Dates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.date_type.code(),
+            self.date1(),
+            self.date2()
+        )
+    }
+}
+
+/// A four character Date1/Date2 year, with `u` for unspecified digits.
+fn year_bytes(date: Date) -> Option<[u8; 4]> {
+    let s = match date.precision() {
+        Precision::Century(y) if (0..=9999).contains(&y) => format!("{:02}uu", y / 100),
+        Precision::Decade(y) if (0..=9999).contains(&y) => format!("{:03}u", y / 10),
+        _ if (0..=9999).contains(&date.year()) => format!("{:04}", date.year()),
+        _ => return None,
+    };
+    let mut out = [0u8; 4];
+    out.copy_from_slice(s.as_bytes());
+    Some(out)
+}
+
+fn is_certain(date: Date) -> bool {
+    date.certainty() == Certainty::Certain
+}
+
+/// # MARC 21
+impl Edtf {
+    /// Generates 008/06-14. See the [crate::marc] module documentation.
+    ///
+    /// 008 can only hold years, except in a detailed date (`e`), so certain dates with a month or
+    /// day use that, and everything else is reduced to its year. Uncertain or approximate dates
+    /// become questionable dates (`q`), with a masked decade or century written as its first and
+    /// last years. An open start cannot be expressed, so it is written as unknown.
+    ///
+    /// This is lossy for intervals: a questionable date is one date, not an interval, and 008
+    /// has no way to mark the ends of an interval uncertain, so intervals always become multiple
+    /// dates (`m`) and any uncertainty or approximation on their ends is dropped.
+    ///
+    /// Returns None for negative years, years after 9999, and `Y`-years.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let marc = |s| Edtf::parse(s).unwrap().to_marc_008().map(|m| m.to_string());
+    /// assert_eq!(marc("1984-03-XX").as_deref(), Some("e198403uu"));
+    /// assert_eq!(marc("1984~").as_deref(), Some("q19841984"));
+    /// assert_eq!(marc("196X?").as_deref(), Some("q19601969"));
+    /// assert_eq!(marc("1984?/1990").as_deref(), Some("m19841990"));
+    /// assert_eq!(marc("1984/..").as_deref(), Some("m19849999"));
+    /// ```
+    pub fn to_marc_008(&self) -> Option<Marc008Dates> {
+        const BLANK: [u8; 4] = *b"    ";
+        const UNKNOWN: [u8; 4] = *b"uuuu";
+        const ONGOING: [u8; 4] = *b"9999";
+        let (date_type, date1, date2) = match *self {
+            Edtf::Date(d) if is_certain(d) => match d.precision() {
+                Precision::Month(y, m) | Precision::DayOfMonth(y, m) if (0..=9999).contains(&y) => {
+                    let mut date2 = [0u8; 4];
+                    date2.copy_from_slice(format!("{:02}uu", m).as_bytes());
+                    (DateType::Detailed, year_bytes(d)?, date2)
+                }
+                Precision::Day(y, m, day) if (0..=9999).contains(&y) => {
+                    let mut date2 = [0u8; 4];
+                    date2.copy_from_slice(format!("{:02}{:02}", m, day).as_bytes());
+                    (DateType::Detailed, year_bytes(d)?, date2)
+                }
+                _ => (DateType::Single, year_bytes(d)?, BLANK),
+            },
+            Edtf::Date(d) => {
+                let year = |y: i32| year_bytes(Date::from_precision_opt(Precision::Year(y))?);
+                match d.precision() {
+                    Precision::Decade(y) => (DateType::Questionable, year(y)?, year(y + 9)?),
+                    Precision::Century(y) => (DateType::Questionable, year(y)?, year(y + 99)?),
+                    _ => (DateType::Questionable, year_bytes(d)?, year_bytes(d)?),
+                }
+            }
+            Edtf::DateTime(dt) => {
+                return Edtf::Date(Date::from_complete(dt.date())).to_marc_008();
+            }
+            Edtf::Interval(a, b) => (DateType::Multiple, year_bytes(a)?, year_bytes(b)?),
+            Edtf::IntervalFrom(a, t) => {
+                let date2 = match t {
+                    Terminal::Open => ONGOING,
+                    Terminal::Unknown => UNKNOWN,
+                };
+                (DateType::Multiple, year_bytes(a)?, date2)
+            }
+            Edtf::IntervalTo(_, b) => (DateType::Multiple, UNKNOWN, year_bytes(b)?),
+            Edtf::YYear(_) => return None,
+        };
+        Some(Marc008Dates {
+            date_type,
+            date1,
+            date2,
+        })
+    }
+}
+
+#[test]
+fn marc_to_edtf() {
+    let edtf = |s: &str| Marc008Dates::parse(s).map(|m| m.to_edtf().map(|e| e.to_string()));
+    let ok = |s: &str| Ok(Some(s.to_string()));
+    assert_eq!(edtf("s1984    "), ok("1984"));
+    assert_eq!(edtf("s1984||||"), ok("1984"));
+    assert_eq!(edtf("s198u    "), ok("198X"));
+    assert_eq!(edtf("s19uu    "), ok("19XX"));
+    assert_eq!(edtf("s1uuu    "), ok("10XX/19XX"));
+    assert_eq!(edtf("suuuu    "), Ok(None));
+    assert_eq!(edtf("e19840315"), ok("1984-03-15"));
+    assert_eq!(edtf("e198403uu"), ok("1984-03"));
+    assert_eq!(edtf("e198403  "), ok("1984-03"));
+    assert_eq!(edtf("m19841990"), ok("1984/1990"));
+    assert_eq!(edtf("m19uu1990"), ok("19XX/1990"));
+    assert_eq!(edtf("m19849999"), ok("1984/.."));
+    assert_eq!(edtf("m1984uuuu"), ok("1984/"));
+    assert_eq!(edtf("muuuu1990"), ok("/1990"));
+    assert_eq!(edtf("muuuuuuuu"), Ok(None));
+    assert_eq!(edtf("i19101950"), ok("1910/1950"));
+    assert_eq!(edtf("k19201930"), ok("1920/1930"));
+    assert_eq!(edtf("q19631963"), ok("1963?"));
+    assert_eq!(edtf("q1963    "), ok("1963?"));
+    assert_eq!(edtf("q196u196u"), ok("196X?"));
+    assert_eq!(edtf("q19601969"), ok("196X?"));
+    assert_eq!(edtf("q19001999"), ok("19XX?"));
+    // one year in a range that isn't a decade or century
+    assert_eq!(edtf("q19631966"), Ok(None));
+    assert_eq!(edtf("q19611970"), Ok(None));
+    assert_eq!(edtf("q1963uuuu"), Ok(None));
+    assert_eq!(edtf("q196u1970"), Ok(None));
+    assert_eq!(edtf("u1984uuuu"), ok("1984/"));
+    assert_eq!(edtf("c19849999"), ok("1984/.."));
+    assert_eq!(edtf("d19841990"), ok("1984/1990"));
+    assert_eq!(edtf("nuuuuuuuu"), Ok(None));
+    assert_eq!(edtf("n        "), Ok(None));
+    assert_eq!(edtf("|||||||||"), Ok(None));
+    // no
+    assert_eq!(edtf("b19841990"), Err(ParseError::Invalid));
+    assert_eq!(edtf("s1u84    "), Err(ParseError::Invalid));
+    assert_eq!(edtf("s        "), Err(ParseError::Invalid));
+    assert_eq!(edtf("s1984"), Err(ParseError::Invalid));
+    assert_eq!(edtf("m19901984"), Err(ParseError::OutOfRange));
+    assert_eq!(edtf("e19830229"), Err(ParseError::OutOfRange));
+    assert_eq!(edtf("e19831300"), Err(ParseError::Invalid));
+    assert_eq!(edtf("e198u0301"), Err(ParseError::Invalid));
+}
+
+#[test]
+fn edtf_to_marc() {
+    let marc = |s: &str| Edtf::parse(s).unwrap().to_marc_008().map(|m| m.to_string());
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(marc("1984"), some("s1984    "));
+    assert_eq!(marc("198X"), some("s198u    "));
+    assert_eq!(marc("19XX"), some("s19uu    "));
+    assert_eq!(marc("1984-XX"), some("s1984    "));
+    assert_eq!(marc("1984-03"), some("e198403uu"));
+    assert_eq!(marc("1984-03-15"), some("e19840315"));
+    assert_eq!(marc("1984-03-15T10:00:00Z"), some("e19840315"));
+    assert_eq!(marc("1984?"), some("q19841984"));
+    assert_eq!(marc("1984/1990"), some("m19841990"));
+    assert_eq!(marc("1984-05/1990-01-02"), some("m19841990"));
+    assert_eq!(marc("196X~"), some("q19601969"));
+    assert_eq!(marc("19XX?"), some("q19001999"));
+    assert_eq!(marc("1984?/1990"), some("m19841990"));
+    assert_eq!(marc("1984?/1990~"), some("m19841990"));
+    assert_eq!(marc("1984/"), some("m1984uuuu"));
+    assert_eq!(marc("../1990"), some("muuuu1990"));
+    assert_eq!(marc("-0044"), None);
+    assert_eq!(marc("Y20000"), None);
+}
+
+#[test]
+fn marc_roundtrip() {
+    for &s in &[
+        "s1984    ",
+        "s198u    ",
+        "s19uu    ",
+        "e19840315",
+        "e198403uu",
+        "m19841990",
+        "m19849999",
+        "m1984uuuu",
+        "muuuu1990",
+        "q19601969",
+        "q19001999",
+        "q19631963",
+    ] {
+        let edtf = Marc008Dates::parse(s).unwrap().to_edtf().unwrap();
+        assert_eq!(
+            edtf.to_marc_008().map(|m| m.to_string()).as_deref(),
+            Some(s)
+        );
+    }
+}
@@ -0,0 +1,429 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # OpenStreetMap `start_date` / `end_date` tags
+//!
+//! Converts the [OSM date convention](https://wiki.openstreetmap.org/wiki/Key:start_date) to and
+//! from [level_1::Edtf][Edtf].
+//!
+//! | OSM                         | EDTF                  |
+//! | ---                         | ----                  |
+//! | `1855`, `1855-03-15`        | `1855`, `1855-03-15`  |
+//! | `~1855`                     | `1855~`               |
+//! | `1850s`, `~1850s`           | `185X`, `185X~`       |
+//! | `C18`, `~C18`               | `17XX`, `17XX~`       |
+//! | `early 1850s`               | `1850/1853`           |
+//! | `mid 1850s`                 | `1854/1856`           |
+//! | `late 1850s`                | `1857/1859`           |
+//! | `early C19`                 | `1800/1833`           |
+//! | `mid C19`                   | `1834/1866`           |
+//! | `late C19`                  | `1867/1899`           |
+//! | `before 1900`               | `../1899`             |
+//! | `after 1900`                | `1901/..`             |
+//! | `1855..1860`                | `1855/1860`           |
+//!
+//! Centuries are read the way they are usually meant on OSM, i.e. `C18` is the 1700s
+//! ([Precision::Century]), not the strict 1701–1800.
+//!
+//! ```
+//! use edtf::level_1::Edtf;
+//! let edtf = Edtf::from_osm("mid C19").unwrap();
+//! assert_eq!(edtf, Edtf::parse("1834/1866").unwrap());
+//! assert_eq!(edtf.to_osm().as_deref(), Some("mid C19"));
+//! ```
+
+#[allow(unused_imports)]
+use nom::{
+    branch as nb, bytes::complete as nbc, character as nch, character::complete as ncc,
+    combinator as nc, error::ParseError as NomParseError, sequence as ns, Finish, IResult, ParseTo,
+    Parser,
+};
+
+use crate::common::{hyphen, two_digits, year_n, StrResult};
+use crate::helpers::ParserExt;
+use crate::level_1::{Certainty, Date, Edtf, Precision, Terminal};
+use crate::ParseError;
+
+/// Which third of a decade or century.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Part {
+    Early,
+    Mid,
+    Late,
+}
+
+impl Part {
+    const ALL: [Part; 3] = [Part::Early, Part::Mid, Part::Late];
+
+    /// Offsets of the first and last year into a decade or century of `len` years.
+    fn years(self, len: i32) -> (i32, i32) {
+        match (self, len) {
+            (Part::Early, 10) => (0, 3),
+            (Part::Mid, 10) => (4, 6),
+            (Part::Late, 10) => (7, 9),
+            (Part::Early, _) => (0, 33),
+            (Part::Mid, _) => (34, 66),
+            (Part::Late, _) => (67, 99),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Part::Early => "early",
+            Part::Mid => "mid",
+            Part::Late => "late",
+        }
+    }
+}
+
+/// A single OSM date, which may stand for a span of years.
+#[derive(Debug, Copy, Clone)]
+enum Term {
+    Date(Date),
+    Span(Date, Date),
+}
+
+impl Term {
+    fn first(self) -> Date {
+        match self {
+            Term::Date(d) | Term::Span(d, _) => d,
+        }
+    }
+    fn last(self) -> Date {
+        match self {
+            Term::Date(d) | Term::Span(_, d) => d,
+        }
+    }
+}
+
+enum Unvalidated {
+    Date(i32, u8, u8),
+    Decade(i32),
+    Century(i32),
+    Part(Part, Box<Unvalidated>),
+}
+
+impl Unvalidated {
+    fn validate(self, certainty: Certainty) -> Result<Term, ParseError> {
+        let prec = match self {
+            Unvalidated::Date(y, m, d) => {
+                return Date::from_ymd_opt(y, m as u32, d as u32)
+                    .map(|d| Term::Date(d.and_certainty(certainty)))
+                    .ok_or(ParseError::OutOfRange)
+            }
+            Unvalidated::Decade(y) if y % 10 == 0 => Precision::Decade(y),
+            Unvalidated::Century(c) if c >= 1 => Precision::Century((c - 1) * 100),
+            Unvalidated::Part(part, inner) => {
+                let (start, len) = match *inner {
+                    Unvalidated::Decade(y) if y % 10 == 0 => (y, 10),
+                    Unvalidated::Century(c) if c >= 1 => ((c - 1) * 100, 100),
+                    _ => return Err(ParseError::Invalid),
+                };
+                let (a, b) = part.years(len);
+                let year = |y: i32| {
+                    Date::from_ymd_opt(y, 0, 0)
+                        .map(|d| d.and_certainty(certainty))
+                        .ok_or(ParseError::OutOfRange)
+                };
+                return Ok(Term::Span(year(start + a)?, year(start + b)?));
+            }
+            _ => return Err(ParseError::Invalid),
+        };
+        Date::from_precision_opt(prec)
+            .map(|d| Term::Date(d.and_certainty(certainty)))
+            .ok_or(ParseError::OutOfRange)
+    }
+}
+
+enum Parsed {
+    Single(Certainty, Unvalidated),
+    Range((Certainty, Unvalidated), (Certainty, Unvalidated)),
+    Before(Unvalidated),
+    After(Unvalidated),
+}
+
+fn osm(remain: &str) -> StrResult<'_, Parsed> {
+    let before = ns::preceded(nbc::tag_no_case("before "), point).map(Parsed::Before);
+    let after = ns::preceded(nbc::tag_no_case("after "), point).map(Parsed::After);
+    let range = term
+        .and_ignore(nbc::tag(".."))
+        .and(term)
+        .map(|(a, b)| Parsed::Range(a, b));
+    let single = term.map(|(c, t)| Parsed::Single(c, t));
+    before
+        .or(after)
+        .or(range.complete())
+        .or(single)
+        .complete()
+        .parse(remain)
+}
+
+/// `~1850s`, `early C19`, etc
+fn term(remain: &str) -> StrResult<'_, (Certainty, Unvalidated)> {
+    let approx = ncc::char('~')
+        .optional()
+        .map(|t| t.map_or(Certainty::Certain, |_| Certainty::Approximate));
+    let part = nb::alt((
+        nbc::tag_no_case("early ").map(|_| Part::Early),
+        nbc::tag_no_case("mid ").map(|_| Part::Mid),
+        nbc::tag_no_case("late ").map(|_| Part::Late),
+    ))
+    .and(decade.or(century))
+    .map(|(p, inner)| (Certainty::Certain, Unvalidated::Part(p, Box::new(inner))));
+    part.or(approx.and(point)).parse(remain)
+}
+
+/// `1855`, `1855-03`, `1855-03-15`, `1850s`, `C18`
+fn point(remain: &str) -> StrResult<'_, Unvalidated> {
+    decade.or(century).or(date).parse(remain)
+}
+
+fn decade(remain: &str) -> StrResult<'_, Unvalidated> {
+    year_n(4)
+        .and_ignore(ncc::char('s'))
+        .map(Unvalidated::Decade)
+        .parse(remain)
+}
+
+fn century(remain: &str) -> StrResult<'_, Unvalidated> {
+    ns::preceded(
+        ncc::char('C'),
+        nbc::take_while_m_n(1, 2, |c: char| c.is_ascii_digit()),
+    )
+    .map(|digits: &str| Unvalidated::Century(digits.parse().unwrap_or(0)))
+    .parse(remain)
+}
+
+fn date(remain: &str) -> StrResult<'_, Unvalidated> {
+    year_n(4)
+        .and(
+            ns::preceded(hyphen, two_digits::<u8>)
+                .and(ns::preceded(hyphen, two_digits::<u8>).optional())
+                .optional(),
+        )
+        .map(|(y, md)| {
+            let (m, d) = md.map_or((0, 0), |(m, d)| (m, d.unwrap_or(0)));
+            Unvalidated::Date(y, m, d)
+        })
+        .parse(remain)
+}
+
+/// Moves a date one unit of its own precision forwards or backwards.
+fn step(date: Date, forward: bool) -> Option<Date> {
+    let sign = if forward { 1 } else { -1 };
+    let prec = match date.precision() {
+        Precision::Century(y) => Precision::Century(y.checked_add(100 * sign)?),
+        Precision::Decade(y) => Precision::Decade(y.checked_add(10 * sign)?),
+        Precision::Year(y) => Precision::Year(y.checked_add(sign)?),
+        Precision::Month(y, m) => {
+            let months = (y as i64) * 12 + (m as i64 - 1) + sign as i64;
+            Precision::Month(
+                months.div_euclid(12) as i32,
+                months.rem_euclid(12) as u32 + 1,
+            )
+        }
+        Precision::Day(..) => {
+            let next = date.complete()?.add_days(sign as i64)?;
+            Precision::Day(next.year(), next.month(), next.day())
+        }
+        _ => return None,
+    };
+    Date::from_precision_opt(prec).map(|d| d.and_certainty(date.certainty()))
+}
+
+fn format_date(date: Date) -> Option<String> {
+    let tilde = match date.certainty() {
+        Certainty::Certain => "",
+        Certainty::Approximate => "~",
+        Certainty::Uncertain | Certainty::ApproximateUncertain => return None,
+    };
+    if date.year() < 0 {
+        return None;
+    }
+    let body = match date.precision() {
+        Precision::Century(y) => format!("C{}", y / 100 + 1),
+        Precision::Decade(y) => format!("{:04}s", y),
+        Precision::Year(y) | Precision::MonthOfYear(y) | Precision::DayOfYear(y) => {
+            format!("{:04}", y)
+        }
+        Precision::Month(y, m) | Precision::DayOfMonth(y, m) => format!("{:04}-{:02}", y, m),
+        Precision::Day(y, m, d) => format!("{:04}-{:02}-{:02}", y, m, d),
+        Precision::Season(..) => return None,
+    };
+    Some(format!("{}{}", tilde, body))
+}
+
+/// Recognises `early 1850s`, `mid C19` etc.
+fn format_part(a: Date, b: Date) -> Option<String> {
+    let (ya, yb) = match (a.precision_certainty(), b.precision_certainty()) {
+        ((Precision::Year(ya), Certainty::Certain), (Precision::Year(yb), Certainty::Certain))
+            if ya >= 0 =>
+        {
+            (ya, yb)
+        }
+        _ => return None,
+    };
+    Part::ALL.iter().find_map(|&part| {
+        let decade = ya - ya % 10;
+        let century = ya - ya % 100;
+        if part.years(10) == (ya - decade, yb - decade) {
+            Some(format!("{} {:04}s", part.as_str(), decade))
+        } else if part.years(100) == (ya - century, yb - century) {
+            Some(format!("{} C{}", part.as_str(), century / 100 + 1))
+        } else {
+            None
+        }
+    })
+}
+
+/// # OpenStreetMap
+impl Edtf {
+    /// Parses an OpenStreetMap `start_date` or `end_date` value. See the [crate::osm] module
+    /// documentation for the supported syntax.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// assert_eq!(Edtf::from_osm("~1850s"), Edtf::parse("185X~"));
+    /// assert_eq!(Edtf::from_osm("before 1900"), Edtf::parse("../1899"));
+    /// assert_eq!(Edtf::from_osm("early 1850s..C20"), Edtf::parse("1850/19XX"));
+    /// ```
+    pub fn from_osm(input: &str) -> Result<Self, ParseError> {
+        let (_, parsed) = osm(input.trim()).map_err(|_| ParseError::Invalid)?;
+        let edtf = match parsed {
+            Parsed::Single(c, t) => match t.validate(c)? {
+                Term::Date(d) => Edtf::Date(d),
+                Term::Span(a, b) => Edtf::Interval(a, b),
+            },
+            Parsed::Range((ca, a), (cb, b)) => {
+                Edtf::Interval(a.validate(ca)?.first(), b.validate(cb)?.last())
+            }
+            Parsed::Before(p) => {
+                let first = p.validate(Certainty::Certain)?.first();
+                Edtf::IntervalTo(
+                    Terminal::Open,
+                    step(first, false).ok_or(ParseError::OutOfRange)?,
+                )
+            }
+            Parsed::After(p) => {
+                let last = p.validate(Certainty::Certain)?.last();
+                Edtf::IntervalFrom(
+                    step(last, true).ok_or(ParseError::OutOfRange)?,
+                    Terminal::Open,
+                )
+            }
+        };
+        Ok(edtf)
+    }
+
+    /// Formats as an OpenStreetMap `start_date` or `end_date` value.
+    ///
+    /// OSM has no way to say "some month in 2019", so unspecified months and days are widened to
+    /// the year or month they belong to, and date-times are reduced to their date. Returns None for
+    /// things OSM cannot express: uncertainty (`?` or `%`), seasons, negative years, `Y`-years and
+    /// unknown interval terminals.
+    ///
+    /// ```
+    /// use edtf::level_1::Edtf;
+    /// let osm = |s| Edtf::parse(s).unwrap().to_osm();
+    /// assert_eq!(osm("1850/1853").as_deref(), Some("early 1850s"));
+    /// assert_eq!(osm("1901/..").as_deref(), Some("after 1900"));
+    /// assert_eq!(osm("2019-XX").as_deref(), Some("2019"));
+    /// assert_eq!(osm("2019?"), None);
+    /// ```
+    pub fn to_osm(&self) -> Option<String> {
+        match *self {
+            Edtf::Date(d) => format_date(d),
+            Edtf::Interval(a, b) => format_part(a, b)
+                .or_else(|| Some(format!("{}..{}", format_date(a)?, format_date(b)?))),
+            Edtf::IntervalTo(Terminal::Open, d) if d.certainty() == Certainty::Certain => {
+                Some(format!("before {}", format_date(step(d, true)?)?))
+            }
+            Edtf::IntervalFrom(d, Terminal::Open) if d.certainty() == Certainty::Certain => {
+                Some(format!("after {}", format_date(step(d, false)?)?))
+            }
+            Edtf::DateTime(dt) => format_date(Date::from_complete(dt.date())),
+            Edtf::IntervalTo(..) | Edtf::IntervalFrom(..) | Edtf::YYear(_) => None,
+        }
+    }
+}
+
+#[test]
+fn osm_parse() {
+    let parse = |s: &str| Edtf::from_osm(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(parse("1855"), ok("1855"));
+    assert_eq!(parse("1855-03"), ok("1855-03"));
+    assert_eq!(parse("1855-03-15"), ok("1855-03-15"));
+    assert_eq!(parse("~1855"), ok("1855~"));
+    assert_eq!(parse("~1855-03-15"), ok("1855-03-15~"));
+    assert_eq!(parse("1850s"), ok("185X"));
+    assert_eq!(parse("~1850s"), ok("185X~"));
+    assert_eq!(parse("C18"), ok("17XX"));
+    assert_eq!(parse("C1"), ok("00XX"));
+    assert_eq!(parse("~C18"), ok("17XX~"));
+    assert_eq!(parse("early 1850s"), ok("1850/1853"));
+    assert_eq!(parse("mid 1850s"), ok("1854/1856"));
+    assert_eq!(parse("late 1850s"), ok("1857/1859"));
+    assert_eq!(parse("early C19"), ok("1800/1833"));
+    assert_eq!(parse("mid C19"), ok("1834/1866"));
+    assert_eq!(parse("late C19"), ok("1867/1899"));
+    assert_eq!(parse("before 1900"), ok("../1899"));
+    assert_eq!(parse("before 1900-01"), ok("../1899-12"));
+    assert_eq!(parse("before 1900-03-01"), ok("../1900-02-28"));
+    assert_eq!(parse("before 1850s"), ok("../184X"));
+    assert_eq!(parse("after 1900"), ok("1901/.."));
+    assert_eq!(parse("after C19"), ok("19XX/.."));
+    assert_eq!(parse("1855..1860"), ok("1855/1860"));
+    assert_eq!(parse("~1855..1860-05"), ok("1855~/1860-05"));
+    assert_eq!(parse("late 1850s..mid 1860s"), ok("1857/1866"));
+    // no
+    assert_eq!(parse("1855s"), Err(ParseError::Invalid));
+    assert_eq!(parse("C0"), Err(ParseError::Invalid));
+    assert_eq!(parse("early 1855"), Err(ParseError::Invalid));
+    assert_eq!(parse("~early 1850s"), Err(ParseError::Invalid));
+    assert_eq!(parse("1855-13"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("1855.."), Err(ParseError::Invalid));
+    assert_eq!(parse("sometime"), Err(ParseError::Invalid));
+}
+
+#[test]
+fn osm_roundtrip() {
+    for &s in &[
+        "1855",
+        "1855-03-15",
+        "~1855",
+        "1850s",
+        "~1850s",
+        "C18",
+        "~C18",
+        "early 1850s",
+        "mid 1850s",
+        "late 1850s",
+        "early C19",
+        "mid C19",
+        "late C19",
+        "before 1900",
+        "after 1900",
+        "before 1850s",
+        "1855..1860",
+        "~1855..1860-05",
+    ] {
+        assert_eq!(Edtf::from_osm(s).unwrap().to_osm().as_deref(), Some(s));
+    }
+}
+
+#[test]
+fn osm_format_unsupported() {
+    let osm = |s: &str| Edtf::parse(s).unwrap().to_osm();
+    assert_eq!(osm("2019%"), None);
+    assert_eq!(osm("2019-21"), None);
+    assert_eq!(osm("-0500"), None);
+    assert_eq!(osm("Y20000"), None);
+    assert_eq!(osm("2019/"), None);
+    assert_eq!(osm("../2019?"), None);
+    assert_eq!(osm("2019-05-XX").as_deref(), Some("2019-05"));
+    assert_eq!(osm("2019-05-03T10:00:00Z").as_deref(), Some("2019-05-03"));
+}
@@ -0,0 +1,325 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Radiocarbon dates
+//!
+//! Radiocarbon and other scientific dates are usually given as an age Before Present (BP), where
+//! the present is fixed at 1950, with an error margin: `3200 ± 40 BP`. Calibrated dates are
+//! marked `cal BP`, or given as a range of calendar years like `cal BC 1500–1400`. [BpDate]
+//! parses these and converts them to EDTF.
+//!
+//! ```
+//! use edtf::radiocarbon::{BpDate, BpEdtf};
+//!
+//! let date = BpDate::parse("3200 ± 40 BP").unwrap();
+//! assert_eq!((date.age(), date.error()), (3200, 40));
+//! assert_eq!(date.to_edtf().unwrap().to_string(), "-1290~/-1210~");
+//!
+//! let cal = BpDate::parse("cal BC 1500–1400").unwrap();
+//! assert_eq!(cal.to_string(), "3399 ± 50 cal BP");
+//! assert_eq!(cal.to_edtf().unwrap().to_string(), "-1499~/-1399~");
+//! ```
+//!
+//! Ages that reach beyond the four digit years EDTF allows in a [Date] are converted to a
+//! level 2 [ScientificYear], rounded to the precision of the error margin, so `45,000 ± 2,000
+//! BP` becomes `Y-43E3S2`. Those cannot be marked approximate, but the significant digits say
+//! the same thing.
+
+use core::convert::TryInto;
+use core::fmt;
+
+use crate::era::{n_digits, parse_number, Era, EraYear, PRESENT};
+use crate::helpers::inside_9999;
+use crate::level_1::{Certainty, Date, Edtf, YYear};
+use crate::level_2::ScientificYear;
+use crate::ParseError;
+
+/// An age Before Present (1950), with an error margin, e.g. `3200 ± 40 BP`. See the [module
+/// level documentation](crate::radiocarbon).
+///
+/// The [Display](fmt::Display) implementation writes e.g. `3200 ± 40 BP` or `3200 ± 40 cal BP`,
+/// and leaves out a zero error margin.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BpDate {
+    age: i64,
+    error: i64,
+    calibrated: bool,
+}
+
+/// What a [BpDate] converts to. See [BpDate::to_edtf].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BpEdtf {
+    /// An approximate year or interval of years, or a [YYear] for deep time with a small error
+    /// margin
+    Edtf(Edtf),
+    /// Deep time, rounded to the precision of the error margin
+    Scientific(ScientificYear),
+}
+
+impl BpDate {
+    /// Creates an uncalibrated BP date. Returns None if `error` is negative.
+    pub fn new(age: i64, error: i64) -> Option<Self> {
+        if error < 0 {
+            return None;
+        }
+        Some(BpDate {
+            age,
+            error,
+            calibrated: false,
+        })
+    }
+
+    /// Marks this date as calibrated, i.e. already converted to calendar years.
+    pub fn calibrated(self) -> Self {
+        BpDate {
+            calibrated: true,
+            ..self
+        }
+    }
+
+    /// Gets the age in years before 1950.
+    pub fn age(&self) -> i64 {
+        self.age
+    }
+
+    /// Gets the error margin in years, which applies in both directions.
+    pub fn error(&self) -> i64 {
+        self.error
+    }
+
+    /// Whether this is a calibrated date.
+    pub fn is_calibrated(&self) -> bool {
+        self.calibrated
+    }
+
+    /// The smallest date covering a range of astronomical years. An odd number of years has no
+    /// middle year, so the range is widened by one year.
+    fn from_years(older: i64, younger: i64) -> Option<Self> {
+        let oldest_age = PRESENT.checked_sub(older)?;
+        let youngest_age = PRESENT.checked_sub(younger)?;
+        let span = oldest_age.checked_sub(youngest_age)?;
+        let age = youngest_age.checked_add(span / 2)?;
+        Self::new(age, span - span / 2)
+    }
+
+    /// Parses a BP date, or a calibrated range of calendar years. Accepts:
+    ///
+    /// - `3200 ± 40 BP`, `3200±40 BP`, `3200 +/- 40 BP` and `3,200 BP`
+    /// - `3200 ± 40 cal BP`, `3200 calBP` or `3200 cal. BP` for calibrated dates
+    /// - `3200 ± 40 uncal BP` or `3200 ± 40 RCYBP` for explicitly uncalibrated dates
+    /// - `cal BC 1500–1400`, `1500-1400 cal BC`, `cal AD 1200` and the same with BCE/CE, which
+    ///   are always calibrated
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let normalised = input
+            .replace("+/-", "±")
+            .replace("+-", "±")
+            .replace('±', " ± ")
+            .replace(['–', '—'], "-");
+        let mut tokens: Vec<String> = Vec::new();
+        for token in normalised.split_whitespace() {
+            let upper = token.to_ascii_uppercase();
+            match upper.trim_end_matches('.') {
+                "CALBP" => tokens.extend(["CAL", "BP"].iter().map(|s| s.to_string())),
+                "RCYBP" => tokens.extend(["UNCAL", "BP"].iter().map(|s| s.to_string())),
+                "CAL" | "UNCAL" => tokens.push(upper.trim_end_matches('.').into()),
+                _ => tokens.push(upper),
+            }
+        }
+        let mut calibrated = None;
+        tokens.retain(|t| match t.as_str() {
+            "CAL" | "UNCAL" if calibrated.is_none() => {
+                calibrated = Some(t == "CAL");
+                false
+            }
+            _ => true,
+        });
+        // the era comes first or last
+        let abbreviation = |t: Option<&String>| t.and_then(|t| Era::from_abbreviation(t));
+        let era = if let Some(era) = abbreviation(tokens.first()) {
+            tokens.remove(0);
+            era
+        } else {
+            let era = abbreviation(tokens.last()).ok_or(ParseError::Invalid)?;
+            tokens.pop();
+            era
+        };
+
+        let numbers: Vec<&str> = tokens.iter().map(|s| s.as_str()).collect();
+        let (first, second, error) = match numbers.as_slice() {
+            [n] => match n.find('-') {
+                Some(ix) if ix > 0 => (&n[..ix], Some(&n[ix + 1..]), "0"),
+                _ => (*n, None, "0"),
+            },
+            [n, "±", e] => (*n, None, *e),
+            [a, "-", b] => (*a, Some(*b), "0"),
+            _ => return Err(ParseError::Invalid),
+        };
+        let first = parse_number(first)?;
+        let second = second.map(parse_number).transpose()?;
+        let error = parse_number(error)?;
+
+        if era == Era::Bp {
+            if second.is_some() {
+                return Err(ParseError::Invalid);
+            }
+            let date = Self::new(first, error).ok_or(ParseError::OutOfRange)?;
+            return Ok(if calibrated == Some(true) {
+                date.calibrated()
+            } else {
+                date
+            });
+        }
+        // calendar years are only radiocarbon dates once calibrated
+        if calibrated != Some(true) {
+            return Err(ParseError::Invalid);
+        }
+        let year = |n| {
+            EraYear::new(n, era)
+                .and_then(|y| y.to_astronomical())
+                .ok_or(ParseError::OutOfRange)
+        };
+        let a = year(first)?;
+        let b = match second {
+            Some(n) => year(n)?,
+            None => a,
+        };
+        let oldest = a.min(b).checked_sub(error);
+        let youngest = a.max(b).checked_add(error);
+        oldest
+            .zip(youngest)
+            .and_then(|(a, b)| Self::from_years(a, b))
+            .map(Self::calibrated)
+            .ok_or(ParseError::OutOfRange)
+    }
+
+    /// Converts to EDTF. The oldest and youngest years within the error margin become an
+    /// interval of approximate years, or a single approximate year if the error margin is zero.
+    ///
+    /// Beyond the four digit years of a [Date], the result is a [ScientificYear] with an
+    /// exponent matching the size of the error margin, or a [YYear] if the error margin is less
+    /// than 10 years. Returns None on overflow.
+    ///
+    /// ```
+    /// use edtf::radiocarbon::BpDate;
+    /// let edtf = |s| BpDate::parse(s).unwrap().to_edtf().unwrap().to_string();
+    /// assert_eq!(edtf("1950 BP"), "0000~");
+    /// assert_eq!(edtf("45,000 ± 2,000 BP"), "Y-43E3S2");
+    /// assert_eq!(edtf("12,345 BP"), "Y-10395");
+    /// ```
+    pub fn to_edtf(&self) -> Option<BpEdtf> {
+        let oldest = PRESENT.checked_sub(self.age.checked_add(self.error)?)?;
+        let youngest = PRESENT.checked_sub(self.age.checked_sub(self.error)?)?;
+        let year = |y: i64| {
+            Date::from_ymd_opt(y.try_into().ok()?, 0, 0)
+                .map(|d| d.and_certainty(Certainty::Approximate))
+        };
+        if inside_9999(oldest) && inside_9999(youngest) {
+            let edtf = if oldest == youngest {
+                Edtf::Date(year(oldest)?)
+            } else {
+                Edtf::Interval(year(oldest)?, year(youngest)?)
+            };
+            return Some(BpEdtf::Edtf(edtf));
+        }
+        let center = PRESENT.checked_sub(self.age)?;
+        if self.error < 10 {
+            let edtf = match YYear::new_opt(center) {
+                Some(y) => Edtf::YYear(y),
+                None => Edtf::Date(year(center)?),
+            };
+            return Some(BpEdtf::Edtf(edtf));
+        }
+        let exponent = n_digits(self.error) - 1;
+        let tens = 10i64.pow(exponent);
+        let mantissa = center.checked_add(tens / 2)?.div_euclid(tens);
+        let sig_digits = n_digits(mantissa.checked_abs()?);
+        ScientificYear::new_or_cal(mantissa, exponent as u16, sig_digits as u16)
+            .ok()
+            .map(BpEdtf::Scientific)
+    }
+}
+
+impl fmt::Display for BpDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.age)?;
+        if self.error != 0 {
+            write!(f, " ± {}", self.error)?;
+        }
+        if self.calibrated {
+            write!(f, " cal")?;
+        }
+        write!(f, " BP")
+    }
+}
+
+impl fmt::Display for BpEdtf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BpEdtf::Edtf(e) => write!(f, "{}", e),
+            BpEdtf::Scientific(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[test]
+fn bp_parse() {
+    let parse = |s: &str| BpDate::parse(s).map(|d| (d.age(), d.error(), d.is_calibrated()));
+    assert_eq!(parse("3200 ± 40 BP"), Ok((3200, 40, false)));
+    assert_eq!(parse("3200±40 BP"), Ok((3200, 40, false)));
+    assert_eq!(parse("3200 +/- 40 BP"), Ok((3200, 40, false)));
+    assert_eq!(parse("3,200 BP"), Ok((3200, 0, false)));
+    assert_eq!(parse("3200 ± 40 B.P."), Ok((3200, 40, false)));
+    assert_eq!(parse("3200 ± 40 cal BP"), Ok((3200, 40, true)));
+    assert_eq!(parse("3200 calBP"), Ok((3200, 0, true)));
+    assert_eq!(parse("3200 cal. BP"), Ok((3200, 0, true)));
+    assert_eq!(parse("3200 ± 40 uncal BP"), Ok((3200, 40, false)));
+    assert_eq!(parse("3200 ± 40 RCYBP"), Ok((3200, 40, false)));
+    assert_eq!(parse("BP 3200"), Ok((3200, 0, false)));
+    // 1500 BC is -1499, 3449 BP
+    assert_eq!(parse("cal BC 1500–1400"), Ok((3399, 50, true)));
+    assert_eq!(parse("1500-1400 cal BC"), Ok((3399, 50, true)));
+    assert_eq!(parse("cal BCE 1500 - 1400"), Ok((3399, 50, true)));
+    assert_eq!(parse("cal BC 1500–1401"), Ok((3399, 50, true)));
+    assert_eq!(parse("cal AD 1200–1300"), Ok((700, 50, true)));
+    assert_eq!(parse("cal AD 1200"), Ok((750, 0, true)));
+    assert_eq!(parse("cal AD 1200 ± 20"), Ok((750, 20, true)));
+    assert_eq!(parse("cal BC 1400–1500"), Ok((3399, 50, true)));
+    // no
+    assert_eq!(parse("3200 ± 40"), Err(ParseError::Invalid));
+    assert_eq!(parse("3200 ± BP"), Err(ParseError::Invalid));
+    assert_eq!(parse("1500–1400 BC"), Err(ParseError::Invalid));
+    assert_eq!(parse("3300-3200 BP"), Err(ParseError::Invalid));
+    assert_eq!(parse("cal BC 0"), Err(ParseError::OutOfRange));
+}
+
+#[test]
+fn bp_to_edtf() {
+    let edtf = |s: &str| BpDate::parse(s).unwrap().to_edtf().map(|e| e.to_string());
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(edtf("3200 ± 40 BP"), some("-1290~/-1210~"));
+    assert_eq!(edtf("3200 BP"), some("-1250~"));
+    assert_eq!(edtf("0 ± 50 BP"), some("1900~/2000~"));
+    assert_eq!(edtf("cal AD 1200–1300"), some("1200~/1300~"));
+    assert_eq!(edtf("11,900 ± 200 BP"), some("Y-99E2S2"));
+    assert_eq!(edtf("45,000 ± 2,000 BP"), some("Y-43E3S2"));
+    assert_eq!(edtf("12,345 ± 5 BP"), some("Y-10395"));
+    assert_eq!(edtf("11,945 ± 5 BP"), some("-9995~"));
+    assert_eq!(edtf("66,000,000 ± 500,000 BP"), some("Y-660E5S3"));
+    assert_eq!(BpDate::new(i64::MAX, 1).unwrap().to_edtf(), None);
+    assert!(matches!(
+        BpDate::new(3200, 40).unwrap().to_edtf(),
+        Some(BpEdtf::Edtf(Edtf::Interval(..)))
+    ));
+}
+
+#[test]
+fn bp_display() {
+    let show = |s: &str| BpDate::parse(s).unwrap().to_string();
+    assert_eq!(show("3200±40 BP"), "3200 ± 40 BP");
+    assert_eq!(show("3200 calBP"), "3200 cal BP");
+    assert_eq!(show("cal BC 1500–1400"), "3399 ± 50 cal BP");
+    assert_eq!(BpDate::new(3200, -1), None);
+}
@@ -0,0 +1,428 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright © 2021 Corporation for Digital Scholarship
+
+//! # Era names and regnal years
+//!
+//! Many records are dated by the year of an era or a reign, like `Meiji 5` or `3 Geo. III`.
+//! An [EraTable] lists eras with their Gregorian start and end dates, and converts those
+//! expressions to EDTF. There are two built-in tables, and you can load your own with
+//! [EraTable::parse_table].
+//!
+//! - [EraTable::japanese_since_1868], the Japanese eras (*nengō*) from Meiji onwards. Years are
+//!   calendar years, so the first and last years of an era are cut short by its start and end
+//!   dates.
+//! - [EraTable::british_since_1760], the regnal years of British monarchs from George III
+//!   onwards. Each regnal year runs from an anniversary of the accession to the day before the
+//!   next.
+//!
+//! The built-in tables are partial. Earlier eras and reigns are not included, so `Genroku 5` or
+//! `2 Will. & Mar.` will not parse with them.
+//!
+//! ```
+//! use edtf::regnal::EraTable;
+//! use edtf::level_1::Edtf;
+//!
+//! let japanese = EraTable::japanese_since_1868();
+//! assert_eq!(japanese.parse("Meiji 45"), Edtf::parse("1912-01-01/1912-07-29"));
+//! assert_eq!(japanese.parse("平成元年"), Edtf::parse("1989-01-08/1989-12-31"));
+//! assert_eq!(japanese.parse("Reiwa 2"), Edtf::parse("2020"));
+//!
+//! let british = EraTable::british_since_1760();
+//! assert_eq!(british.parse("3 Geo. III"), Edtf::parse("1762-10-25/1763-10-24"));
+//! assert_eq!(british.parse("63 & 64 Vict."), Edtf::parse("1899-06-20/1901-01-22"));
+//! ```
+//!
+//! They stop there because the older dates don't map cleanly to Gregorian years. Before 1873,
+//! Japan used a lunisolar calendar, so even Meiji 1 to 5 only approximately match the Gregorian
+//! years given here, and earlier eras' years are lunisolar years. Regnal years before George III
+//! were counted on anniversaries in the Julian calendar. You can load either as a table of your
+//! own with Gregorian dates for each era or reign, at the cost of year boundaries being off by up
+//! to a few weeks.
+
+use core::convert::TryInto;
+
+use crate::level_1::Edtf;
+use crate::{DateComplete, ParseError};
+
+const JAPANESE_SINCE_1868: &str = "
+# https://en.wikipedia.org/wiki/Japanese_era_name
+1868-10-23 1912-07-29 Meiji|明治|M
+1912-07-30 1926-12-24 Taishō|Taisho|大正|T
+1926-12-25 1989-01-07 Shōwa|Showa|昭和|S
+1989-01-08 2019-04-30 Heisei|平成|H
+2019-05-01 .. Reiwa|令和|R
+";
+
+const BRITISH_SINCE_1760: &str = "
+# Accession and death or abdication, the same day the next reign began
+1760-10-25 1820-01-29 George III|Geo. III|Geo. 3
+1820-01-29 1830-06-26 George IV|Geo. IV|Geo. 4
+1830-06-26 1837-06-20 William IV|Will. IV|Gul. IV|Will. 4
+1837-06-20 1901-01-22 Victoria|Vict.|Vic.
+1901-01-22 1910-05-06 Edward VII|Edw. VII|Edw. 7
+1910-05-06 1936-01-20 George V|Geo. V|Geo. 5
+1936-01-20 1936-12-11 Edward VIII|Edw. VIII|Edw. 8
+1936-12-11 1952-02-06 George VI|Geo. VI|Geo. 6
+1952-02-06 2022-09-08 Elizabeth II|Eliz. II|Eliz. 2
+2022-09-08 .. Charles III|Cha. III|Chas. III
+";
+
+/// How the years of an era are counted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Reckoning {
+    /// Year 1 runs from the start of the era to the end of that Gregorian year, and later years
+    /// are Gregorian years. Used for Japanese, Chinese and Korean era names.
+    CalendarYear,
+    /// Year 1 runs from the start of the era to the day before its first anniversary, and so
+    /// on. Used for regnal years.
+    Accession,
+}
+
+/// One era in an [EraTable]: its names, and the Gregorian days it started and ended on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EraEntry {
+    names: Vec<String>,
+    start: DateComplete,
+    end: Option<DateComplete>,
+}
+
+impl EraEntry {
+    /// Creates an era. `end` is the last day of the era, or None if it has not ended. Returns
+    /// None if the name is empty or the era ends before it starts.
+    pub fn new(name: &str, start: DateComplete, end: Option<DateComplete>) -> Option<Self> {
+        if name.trim().is_empty() || matches!(end, Some(end) if end < start) {
+            return None;
+        }
+        Some(EraEntry {
+            names: vec![name.trim().to_string()],
+            start,
+            end,
+        })
+    }
+
+    /// Adds another name or abbreviation the era can be written with.
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.names.push(alias.trim().to_string());
+        self
+    }
+
+    /// Gets the era's main name
+    pub fn name(&self) -> &str {
+        &self.names[0]
+    }
+
+    /// Gets all the names the era can be written with, starting with its main name.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Gets the first day of the era
+    pub fn start(&self) -> DateComplete {
+        self.start
+    }
+
+    /// Gets the last day of the era, if it has ended
+    pub fn end(&self) -> Option<DateComplete> {
+        self.end
+    }
+
+    /// The first and last days of a year of the era. None if the year is 0 or after the era
+    /// ended.
+    fn year_days(&self, year: u32, reckoning: Reckoning) -> Option<(DateComplete, DateComplete)> {
+        let offset: i32 = year.checked_sub(1)?.try_into().ok()?;
+        let (first, next) = match reckoning {
+            Reckoning::CalendarYear => {
+                let y = self.start.year().checked_add(offset)?;
+                let first = DateComplete::from_ymd_opt(y, 1, 1)?.max(self.start);
+                (first, DateComplete::from_ymd_opt(y.checked_add(1)?, 1, 1)?)
+            }
+            Reckoning::Accession => {
+                let y = self.start.year().checked_add(offset)?;
+                (self.anniversary(y)?, self.anniversary(y.checked_add(1)?)?)
+            }
+        };
+        let last = next.add_days(-1)?;
+        let last = match self.end {
+            Some(end) => last.min(end),
+            None => last,
+        };
+        if first > last {
+            return None;
+        }
+        Some((first, last))
+    }
+
+    /// The start date's anniversary in `year`. A 29 February start falls on 1 March in common
+    /// years.
+    fn anniversary(&self, year: i32) -> Option<DateComplete> {
+        let (m, d) = (self.start.month(), self.start.day());
+        DateComplete::from_ymd_opt(year, m, d).or_else(|| DateComplete::from_ymd_opt(year, 3, 1))
+    }
+}
+
+/// A table of eras, which converts era years to EDTF. See the [module level
+/// documentation](crate::regnal).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EraTable {
+    reckoning: Reckoning,
+    entries: Vec<EraEntry>,
+}
+
+impl EraTable {
+    /// Creates an empty table.
+    pub fn new(reckoning: Reckoning) -> Self {
+        EraTable {
+            reckoning,
+            entries: Vec::new(),
+        }
+    }
+
+    /// The Japanese eras from Meiji (1868) onwards, counted in calendar years. Earlier eras are
+    /// not included.
+    pub fn japanese_since_1868() -> Self {
+        Self::parse_table(Reckoning::CalendarYear, JAPANESE_SINCE_1868)
+            .expect("built-in table is valid")
+    }
+
+    /// The reigns of British monarchs from George III (1760) onwards, counted in regnal years.
+    /// Earlier reigns are not included.
+    pub fn british_since_1760() -> Self {
+        Self::parse_table(Reckoning::Accession, BRITISH_SINCE_1760)
+            .expect("built-in table is valid")
+    }
+
+    /// Loads a table from text, with one era per line: its first day, its last day or `..` if
+    /// it has not ended, and then its names separated by `|`. Blank lines and lines starting
+    /// with `#` are skipped.
+    ///
+    /// ```
+    /// use edtf::regnal::{EraTable, Reckoning};
+    /// use edtf::level_1::Edtf;
+    ///
+    /// // Elizabeth I's accession was 17 November 1558 in the Julian calendar
+    /// let table = EraTable::parse_table(Reckoning::Accession, "
+    /// 1558-11-27 1603-04-03 Elizabeth I|Eliz.
+    /// ").unwrap();
+    /// assert_eq!(table.parse("1 Eliz."), Edtf::parse("1558-11-27/1559-11-26"));
+    /// ```
+    pub fn parse_table(reckoning: Reckoning, text: &str) -> Result<Self, ParseError> {
+        let mut table = Self::new(reckoning);
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (start, rest) = first_field(line)?;
+            let (end, names) = first_field(rest)?;
+            let day = |s: &str| {
+                crate::level_1::Date::parse(s)?
+                    .complete()
+                    .ok_or(ParseError::Invalid)
+            };
+            let end = match end {
+                ".." => None,
+                e => Some(day(e)?),
+            };
+            let mut names = names.split('|');
+            let name = names.next().unwrap_or("");
+            let entry = EraEntry::new(name, day(start)?, end).ok_or(ParseError::Invalid)?;
+            table.push(names.fold(entry, EraEntry::with_alias));
+        }
+        Ok(table)
+    }
+
+    /// Adds an era to the table.
+    pub fn push(&mut self, entry: EraEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Gets how the table counts years
+    pub fn reckoning(&self) -> Reckoning {
+        self.reckoning
+    }
+
+    /// Gets the eras in the table
+    pub fn entries(&self) -> &[EraEntry] {
+        &self.entries
+    }
+
+    /// Finds an era by any of its names, ignoring case.
+    pub fn get(&self, name: &str) -> Option<&EraEntry> {
+        let name = name.trim().to_lowercase();
+        self.entries
+            .iter()
+            .find(|e| e.names.iter().any(|n| n.to_lowercase() == name))
+    }
+
+    /// Converts a year of an era to the days it covers, in the most compact EDTF. Returns None
+    /// if there is no such era, or the year is 0 or after the era ended.
+    ///
+    /// ```
+    /// use edtf::regnal::EraTable;
+    /// let japanese = EraTable::japanese_since_1868();
+    /// let showa_64 = japanese.year_to_edtf("Showa", 64).unwrap();
+    /// assert_eq!(showa_64.to_string(), "1989-01-01/1989-01-07");
+    /// ```
+    pub fn year_to_edtf(&self, name: &str, year: u32) -> Option<Edtf> {
+        self.years_to_edtf(self.get(name)?, year, year)
+    }
+
+    fn years_to_edtf(&self, entry: &EraEntry, from: u32, to: u32) -> Option<Edtf> {
+        let (first, _) = entry.year_days(from, self.reckoning)?;
+        let (_, last) = entry.year_days(to, self.reckoning)?;
+        Edtf::from_day_range(first, last)
+    }
+
+    /// Parses an era year like `Meiji 5`, `明治5年`, `H31`, `3 Geo. III` or `Geo. III 3`, or two
+    /// years like `63 & 64 Vict.`, and converts it to EDTF. `元` is accepted for the first year.
+    pub fn parse(&self, input: &str) -> Result<Edtf, ParseError> {
+        let input = input.trim().to_lowercase();
+        let mut found = false;
+        // longest names first, so `S` doesn't shadow `Showa`
+        let mut names: Vec<(&EraEntry, String)> = self
+            .entries
+            .iter()
+            .flat_map(|e| e.names.iter().map(move |n| (e, n.to_lowercase())))
+            .collect();
+        names.sort_by_key(|(_, n)| core::cmp::Reverse(n.len()));
+        for (entry, name) in names {
+            let rest = if let Some(rest) = input.strip_prefix(name.as_str()) {
+                rest
+            } else if let Some(rest) = input.strip_suffix(name.as_str()) {
+                rest
+            } else {
+                continue;
+            };
+            let (from, to) = match parse_years(rest) {
+                Some(years) => years,
+                None => continue,
+            };
+            found = true;
+            if let Some(edtf) = self.years_to_edtf(entry, from, to) {
+                return Ok(edtf);
+            }
+        }
+        Err(if found {
+            ParseError::OutOfRange
+        } else {
+            ParseError::Invalid
+        })
+    }
+}
+
+/// Splits off the first whitespace-separated field of a table line.
+fn first_field(line: &str) -> Result<(&str, &str), ParseError> {
+    line.find(char::is_whitespace)
+        .map(|ix| (&line[..ix], line[ix..].trim_start()))
+        .ok_or(ParseError::Invalid)
+}
+
+/// `5`, `5年`, `元年`, or `63 & 64`
+fn parse_years(s: &str) -> Option<(u32, u32)> {
+    let s = s.trim();
+    let s = s.strip_suffix('年').unwrap_or(s).trim();
+    let year = |s: &str| match s.trim() {
+        "元" => Some(1),
+        s if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => s.parse().ok(),
+        _ => None,
+    };
+    match s.find('&') {
+        Some(ix) => {
+            let (from, to) = (year(&s[..ix])?, year(&s[ix + 1..])?);
+            Some((from, to)).filter(|_| from <= to)
+        }
+        None => year(s).map(|y| (y, y)),
+    }
+}
+
+#[test]
+fn japanese_eras() {
+    let table = EraTable::japanese_since_1868();
+    let parse = |s| table.parse(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(parse("Meiji 1"), ok("1868-10-23/1868-12-31"));
+    assert_eq!(parse("Meiji 5"), ok("1872"));
+    assert_eq!(parse("meiji 5"), ok("1872"));
+    assert_eq!(parse("明治5年"), ok("1872"));
+    assert_eq!(parse("Taishō 15"), ok("1926-01-01/1926-12-24"));
+    assert_eq!(parse("Taisho 15"), ok("1926-01-01/1926-12-24"));
+    assert_eq!(parse("S1"), ok("1926-12-25/1926-12-31"));
+    assert_eq!(parse("Showa 64"), ok("1989-01-01/1989-01-07"));
+    assert_eq!(parse("H31"), ok("2019-01/2019-04"));
+    assert_eq!(parse("令和元年"), ok("2019-05/2019-12"));
+    assert_eq!(parse("Reiwa 100"), ok("2118"));
+    // no
+    assert_eq!(parse("Showa 65"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("Showa 0"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("Showa"), Err(ParseError::Invalid));
+    assert_eq!(parse("Edo 5"), Err(ParseError::Invalid));
+    // before Meiji
+    assert_eq!(parse("Genroku 5"), Err(ParseError::Invalid));
+    assert_eq!(parse("Showa 5 6"), Err(ParseError::Invalid));
+}
+
+#[test]
+fn british_regnal_years() {
+    let table = EraTable::british_since_1760();
+    let parse = |s| table.parse(s).map(|e| e.to_string());
+    let ok = |s: &str| Ok(s.to_string());
+    assert_eq!(parse("1 Geo. III"), ok("1760-10-25/1761-10-24"));
+    assert_eq!(parse("George III 60"), ok("1819-10-25/1820-01-29"));
+    assert_eq!(parse("1 Vict."), ok("1837-06-20/1838-06-19"));
+    assert_eq!(parse("1 & 2 Vict."), ok("1837-06-20/1839-06-19"));
+    assert_eq!(parse("1 Edw. VIII"), ok("1936-01-20/1936-12-11"));
+    assert_eq!(parse("Eliz. II 71"), ok("2022-02-06/2022-09-08"));
+    assert_eq!(parse("1 Chas. III"), ok("2022-09-08/2023-09-07"));
+    // George V, not Geo. VI
+    assert_eq!(parse("1 Geo. V"), ok("1910-05-06/1911-05-05"));
+    assert_eq!(parse("2 Edw. VIII"), Err(ParseError::OutOfRange));
+    assert_eq!(parse("2 & 1 Vict."), Err(ParseError::Invalid));
+    // before George III
+    assert_eq!(parse("1 Anne"), Err(ParseError::Invalid));
+    assert_eq!(table.get("victoria").map(EraEntry::name), Some("Victoria"));
+}
+
+#[test]
+fn custom_tables() {
+    let leap = EraEntry::new(
+        "Leap",
+        DateComplete::from_ymd(2020, 2, 29),
+        Some(DateComplete::from_ymd(2030, 1, 1)),
+    )
+    .unwrap();
+    assert_eq!(
+        leap.year_days(2, Reckoning::Accession),
+        Some((
+            DateComplete::from_ymd(2021, 3, 1),
+            DateComplete::from_ymd(2022, 2, 28)
+        ))
+    );
+    let mut table = EraTable::new(Reckoning::Accession);
+    table.push(leap.with_alias("L"));
+    assert_eq!(
+        table.year_to_edtf("l", 1).unwrap().to_string(),
+        "2020-02-29/2021-02-28"
+    );
+    assert_eq!(
+        table.year_to_edtf("l", 10).unwrap().to_string(),
+        "2029-03-01/2030-01-01"
+    );
+    assert_eq!(table.year_to_edtf("l", 11), None);
+
+    let parse_table = |s| EraTable::parse_table(Reckoning::CalendarYear, s);
+    assert!(parse_table("").unwrap().entries().is_empty());
+    let spaced = parse_table("  2000-01-01   ..   Name|N  ").unwrap();
+    assert_eq!(spaced.entries()[0].names(), &["Name", "N"]);
+    assert_eq!(parse_table("2000-01-01 .."), Err(ParseError::Invalid));
+    assert_eq!(parse_table("2000-01 .. Name"), Err(ParseError::Invalid));
+    assert_eq!(
+        parse_table("2000-01-01 1999-01-01 Name"),
+        Err(ParseError::Invalid)
+    );
+    assert_eq!(
+        parse_table("2000-01-01 .. |Alias"),
+        Err(ParseError::Invalid)
+    );
+}